@@ -0,0 +1,436 @@
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Parser, Subcommand};
+use degen_launch::{Beneficiary, Config, ConfigParams};
+use degen_launch_client::{
+    address_book, lookup_table, pda, quote, AddressBook, InstructionBuilder, LookupTableManager, Profile, Resolver,
+    SimulationReport, Simulator, TransactionBuilder,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[derive(Parser)]
+#[command(name = "degen-cli", about = "Run and administer degen_launch")]
+struct Cli {
+    /// RPC URL; defaults to the profile's
+    #[arg(long, global = true)]
+    url: Option<String>,
+    /// Address book profile; defaults to [provider].cluster in Anchor.toml
+    #[arg(long, global = true)]
+    cluster: Option<String>,
+    #[arg(long, global = true, default_value = "addresses.toml")]
+    address_book: PathBuf,
+    /// Signer: operator, admin or treasurer depending on the command
+    #[arg(long, global = true)]
+    keypair: Option<PathBuf>,
+    #[command(flatten)]
+    tx: TxOptions,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct TxOptions {
+    /// Address lookup tables to compile the transaction against
+    #[arg(long = "lookup-table", global = true)]
+    lookup_tables: Vec<Pubkey>,
+    #[arg(long, global = true)]
+    compute_unit_limit: Option<u32>,
+    /// Priority fee in micro-lamports per compute unit
+    #[arg(long, global = true)]
+    priority_fee: Option<u64>,
+    /// Simulate instead of sending, reporting compute units, errors and token balance changes
+    #[arg(long, global = true)]
+    simulate: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Borrow token B of a whirlpool and dump token A into it inside the loan
+    Flashloan {
+        /// Pool address or address book name
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u16,
+        /// Write an execution receipt seeded with this nonce
+        #[arg(long)]
+        receipt_nonce: Option<u64>,
+    },
+    /// Flash-borrow a mint and route it through whirlpools back into that mint
+    Route {
+        #[arg(long)]
+        mint: String,
+        /// Whirlpools in route order; repeat for each leg
+        #[arg(long = "pool", required = true)]
+        pools: Vec<String>,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u16,
+        /// Profit floor in native units, raised to the config's minimum
+        #[arg(long, default_value_t = 0)]
+        min_profit: u64,
+    },
+    /// Swap between the program vaults on a whirlpool
+    Swap {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u16,
+        /// Swap token B for token A instead of A for B
+        #[arg(long)]
+        b_to_a: bool,
+    },
+    /// Print the spot quote for a whirlpool swap
+    Quote {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        b_to_a: bool,
+    },
+    /// Create or change the program config
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Move vault funds to the treasury; sweeps everything above the working balance without --amount
+    Withdraw {
+        #[arg(long)]
+        mint: String,
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Create a lookup table holding a flash loan's static accounts over the given pools
+    LookupTable {
+        #[arg(long = "pool", required = true)]
+        pools: Vec<String>,
+        /// Lender reserve; found from the first pool's token B when omitted
+        #[arg(long)]
+        reserve: Option<String>,
+        /// Extend this table instead of creating one
+        #[arg(long)]
+        extend: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    Init {
+        /// Largest amount a single flash loan may borrow
+        #[arg(long)]
+        max_borrow_amount: u64,
+        #[command(flatten)]
+        args: ConfigArgs,
+    },
+    /// Change the given settings, keeping the rest
+    Update {
+        #[command(flatten)]
+        args: ConfigArgs,
+        #[arg(long)]
+        max_borrow_amount: Option<u64>,
+        #[arg(long)]
+        new_admin: Option<Pubkey>,
+    },
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    #[arg(long)]
+    lending_program: Option<Pubkey>,
+    /// Defaults to every reserve in the profile on init
+    #[arg(long = "reserve")]
+    reserves: Vec<String>,
+    /// Defaults to every pool in the profile on init
+    #[arg(long = "pool")]
+    pools: Vec<String>,
+    #[arg(long)]
+    min_profit: Option<u64>,
+    /// Defaults to the profile's "treasury" wallet on init
+    #[arg(long)]
+    treasury: Option<String>,
+    #[arg(long)]
+    operator_reward_bps: Option<u16>,
+    /// WALLET:BPS, repeatable
+    #[arg(long = "beneficiary", value_parser = parse_beneficiary)]
+    beneficiaries: Vec<Beneficiary>,
+}
+
+impl ConfigArgs {
+    /// Fills the settings not given on the command line from `current`
+    fn merge(self, profile: &Profile, current: ConfigParams) -> Result<ConfigParams> {
+        Ok(ConfigParams {
+            lending_program: self.lending_program.unwrap_or(current.lending_program),
+            allowed_reserves: non_empty_or(addresses(profile, &self.reserves)?, current.allowed_reserves),
+            allowed_pools: non_empty_or(addresses(profile, &self.pools)?, current.allowed_pools),
+            max_borrow_amount: current.max_borrow_amount,
+            min_profit: self.min_profit.unwrap_or(current.min_profit),
+            treasury: match self.treasury {
+                Some(treasury) => profile.address(&treasury)?,
+                None => current.treasury,
+            },
+            operator_reward_bps: self.operator_reward_bps.unwrap_or(current.operator_reward_bps),
+            beneficiaries: non_empty_or(self.beneficiaries, current.beneficiaries),
+        })
+    }
+}
+
+fn addresses(profile: &Profile, names: &[String]) -> Result<Vec<Pubkey>> {
+    names.iter().map(|name| profile.address(name)).collect()
+}
+
+fn non_empty_or<T>(given: Vec<T>, current: Vec<T>) -> Vec<T> {
+    if given.is_empty() {
+        current
+    } else {
+        given
+    }
+}
+
+fn parse_beneficiary(s: &str) -> Result<Beneficiary> {
+    let (wallet, share_bps) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected WALLET:BPS, got {}", s))?;
+    Ok(Beneficiary {
+        wallet: Pubkey::from_str(wallet)?,
+        share_bps: share_bps.parse()?,
+    })
+}
+
+/// Initial settings taken from the address book profile
+fn default_params(profile: &Profile, max_borrow_amount: u64) -> ConfigParams {
+    ConfigParams {
+        lending_program: profile.programs.lending,
+        allowed_reserves: profile.reserves.values().copied().collect(),
+        allowed_pools: profile.pools.values().copied().collect(),
+        max_borrow_amount,
+        min_profit: 0,
+        treasury: profile.wallets.get("treasury").copied().unwrap_or_default(),
+        operator_reward_bps: 0,
+        beneficiaries: Vec::new(),
+    }
+}
+
+fn current_params(config: Config) -> ConfigParams {
+    ConfigParams {
+        lending_program: config.lending_program,
+        allowed_reserves: config.allowed_reserves,
+        allowed_pools: config.allowed_pools,
+        max_borrow_amount: config.max_borrow_amount,
+        min_profit: config.min_profit,
+        treasury: config.treasury,
+        operator_reward_bps: config.operator_reward_bps,
+        beneficiaries: config.beneficiaries,
+    }
+}
+
+/// Loads the address book profile for `cluster`, or for Anchor.toml's cluster when not given
+fn load_profile(address_book: &Path, cluster: Option<String>) -> Result<Profile> {
+    let cluster = match cluster {
+        Some(cluster) => address_book::cluster_profile(&cluster)?,
+        None => address_book::anchor_cluster(Path::new("Anchor.toml"))?,
+    };
+    let profile = AddressBook::load(address_book)?.profile(&cluster)?.clone();
+    if profile.programs.degen_launch != degen_launch::ID {
+        bail!(
+            "{} profile points at program {}, but this build targets {}",
+            cluster,
+            profile.programs.degen_launch,
+            degen_launch::ID
+        );
+    }
+    Ok(profile)
+}
+
+fn load_keypair(path: Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path,
+        None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("reading {}: {}", path.display(), e))
+}
+
+/// Compiles `builder` into a v0 transaction and simulates or sends it
+fn execute(
+    options: &TxOptions,
+    rpc: &RpcClient,
+    signer: &Keypair,
+    profile: &Profile,
+    mut builder: TransactionBuilder,
+) -> Result<()> {
+    let tables = LookupTableManager::new(rpc, signer);
+    for table in &options.lookup_tables {
+        builder = builder.lookup_table(tables.load(table)?);
+    }
+    if let Some(units) = options.compute_unit_limit {
+        builder = builder.compute_unit_limit(units);
+    }
+    if let Some(price) = options.priority_fee {
+        builder = builder.compute_unit_price(price);
+    }
+
+    let tx = builder.sign(rpc.get_latest_blockhash()?, &[signer])?;
+
+    if options.simulate {
+        let instructions = builder.all_instructions()?;
+        let report = Simulator::new(rpc, profile.programs.lending, profile.programs.whirlpool).simulate(&tx, &instructions)?;
+        print_report(&report, &profit_owners(rpc, profile, signer));
+    } else {
+        let signature = rpc.send_and_confirm_transaction(&tx)?;
+        println!("{}", signature);
+    }
+
+    Ok(())
+}
+
+/// Token account owners whose balances count towards profit: the program vaults,
+/// the operator and the configured beneficiaries
+fn profit_owners(rpc: &RpcClient, profile: &Profile, signer: &Keypair) -> Vec<Pubkey> {
+    let mut owners = vec![pda::vault_authority(), signer.pubkey()];
+    if let Ok(config) = Resolver::new(rpc, profile.programs.whirlpool).config() {
+        owners.extend(config.beneficiaries.iter().map(|b| b.wallet));
+    }
+    owners
+}
+
+fn print_report(report: &SimulationReport, owners: &[Pubkey]) {
+    for log in &report.logs {
+        println!("  {}", log);
+    }
+    match report.units_consumed {
+        Some(units) => println!("units consumed: {}", units),
+        None => println!("units consumed: unknown"),
+    }
+    match (&report.err, &report.program_error) {
+        (None, _) => println!("simulation succeeded"),
+        (Some(_), Some(decoded)) => println!("simulation failed: {}", decoded),
+        (Some(err), None) => println!("simulation failed: {}", err),
+    }
+
+    println!("token balance changes:");
+    for change in report.balance_changes.iter().filter(|c| c.delta() != 0) {
+        println!(
+            "  {} (mint {}, owner {}): {} -> {} ({:+})",
+            change.address,
+            change.mint,
+            change.owner,
+            change.pre,
+            change.post,
+            change.delta()
+        );
+    }
+    println!(
+        "network fee: {} lamports (payer {} {:+} lamports)",
+        report.fee,
+        report.payer,
+        report.payer_lamport_change()
+    );
+    for (mint, net) in report.net_change(owners) {
+        println!("projected profit in {}: {:+}", mint, net);
+    }
+}
+
+/// Creates or extends a lookup table with the static accounts of flash loans over `pools`
+fn lookup_table(
+    rpc: &RpcClient,
+    resolver: &Resolver,
+    signer: &Keypair,
+    pools: &[Pubkey],
+    reserve: Option<Pubkey>,
+    extend: Option<Pubkey>,
+) -> Result<()> {
+    let config = resolver.config()?;
+
+    // Tick arrays on both sides of the price, so either swap direction hits the table
+    let mut resolved = Vec::new();
+    for pool in pools {
+        resolved.push(resolver.whirlpool(pool, true)?.accounts);
+        resolved.push(resolver.whirlpool(pool, false)?.accounts);
+    }
+
+    let lender = match reserve {
+        Some(reserve) => resolver.lender(&config.lending_program, &reserve)?,
+        None => resolver.lender_for_mint(
+            &config.lending_program,
+            &resolved[0].token_mint_b,
+            &config.allowed_reserves,
+        )?,
+    };
+
+    let addresses = lookup_table::static_accounts(&lender, &resolver.whirlpool_program(), &resolved);
+    let tables = LookupTableManager::new(rpc, signer);
+    match extend {
+        Some(table) => println!("{}: added {} addresses", table, tables.extend(&table, &addresses)?),
+        None => println!("{}", tables.create(&addresses)?),
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let Cli { url, cluster, address_book, keypair, tx, command } = Cli::parse();
+    let profile = load_profile(&address_book, cluster)?;
+    let rpc = RpcClient::new_with_commitment(url.unwrap_or_else(|| profile.url.clone()), CommitmentConfig::confirmed());
+
+    // Quotes only read state, so they work without a keypair
+    if let Command::Quote { pool, amount, b_to_a } = &command {
+        let pool = Resolver::new(&rpc, profile.programs.whirlpool).whirlpool(&profile.address(pool)?, !b_to_a)?;
+        println!("{}", quote::whirlpool_spot_quote(&pool, *amount, !b_to_a));
+        return Ok(());
+    }
+
+    let signer = load_keypair(keypair)?;
+    let builder = InstructionBuilder::new(Resolver::new(&rpc, profile.programs.whirlpool), signer.pubkey());
+    let resolver = builder.resolver();
+
+    let transaction = TransactionBuilder::new(signer.pubkey());
+    let transaction = match command {
+        Command::Flashloan { pool, amount, slippage_bps, receipt_nonce } => transaction.flash_loan(
+            builder.execute_flashloan_selfdump(&profile.address(&pool)?, amount, slippage_bps, receipt_nonce)?,
+        ),
+        Command::Route { mint, pools, amount, slippage_bps, min_profit } => {
+            let mint = profile.address(&mint)?;
+            let pools = addresses(&profile, &pools)?;
+            transaction.flash_loan(builder.execute_route(&mint, &pools, amount, slippage_bps, min_profit)?)
+        }
+        Command::Swap { pool, amount, slippage_bps, b_to_a } => {
+            let pool = profile.address(&pool)?;
+            let accounts = resolver.whirlpool(&pool, !b_to_a)?.accounts;
+            let input_mint = if b_to_a { accounts.token_mint_b } else { accounts.token_mint_a };
+            transaction.instruction(builder.swap_via_venue(&pool, &input_mint, amount, slippage_bps)?)
+        }
+        Command::Quote { .. } => unreachable!("quotes return before the keypair is loaded"),
+        Command::Config(ConfigCommand::Init { max_borrow_amount, args }) => transaction
+            .instruction(builder.initialize_config(args.merge(&profile, default_params(&profile, max_borrow_amount))?)),
+        Command::Config(ConfigCommand::Update { args, max_borrow_amount, new_admin }) => {
+            let mut current = current_params(resolver.config()?);
+            current.max_borrow_amount = max_borrow_amount.unwrap_or(current.max_borrow_amount);
+            transaction.instruction(builder.update_config(args.merge(&profile, current)?, new_admin))
+        }
+        Command::Withdraw { mint, amount } => {
+            let mint = profile.address(&mint)?;
+            transaction.instruction(match amount {
+                Some(amount) => builder.withdraw(&mint, amount)?,
+                None => builder.sweep_profits(&mint)?,
+            })
+        }
+        Command::LookupTable { pools, reserve, extend } => {
+            let pools = addresses(&profile, &pools)?;
+            let reserve = reserve.map(|reserve| profile.address(&reserve)).transpose()?;
+            return lookup_table(&rpc, resolver, &signer, &pools, reserve, extend);
+        }
+    };
+
+    execute(&tx, &rpc, &signer, &profile, transaction)
+}
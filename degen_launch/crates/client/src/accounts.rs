@@ -0,0 +1,54 @@
+use solana_sdk::pubkey::Pubkey;
+
+/// Flash loan lender accounts that stay the same from run to run
+#[derive(Clone, Copy, Debug)]
+pub struct LenderAccounts {
+    pub lending_program: Pubkey,
+    pub reserve: Pubkey,
+    pub reserve_liquidity_supply: Pubkey,
+    pub lending_market: Pubkey,
+    pub lending_market_authority: Pubkey,
+    /// Receives the reserve's part of the flash loan fee
+    pub fee_receiver: Pubkey,
+}
+
+impl LenderAccounts {
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        vec![
+            self.lending_program,
+            self.reserve,
+            self.reserve_liquidity_supply,
+            self.lending_market,
+            self.lending_market_authority,
+            self.fee_receiver,
+        ]
+    }
+}
+
+/// A whirlpool and the accounts a swap on it touches
+#[derive(Clone, Copy, Debug)]
+pub struct WhirlpoolAccounts {
+    pub whirlpool: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub oracle: Pubkey,
+    /// Tick arrays around the current price; these move as the price does
+    pub tick_arrays: [Pubkey; 3],
+}
+
+impl WhirlpoolAccounts {
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        let mut addresses = vec![
+            self.whirlpool,
+            self.token_mint_a,
+            self.token_mint_b,
+            self.token_vault_a,
+            self.token_vault_b,
+            self.oracle,
+        ];
+        addresses.extend(self.tick_arrays);
+        addresses
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr};
+
+/// Program IDs a profile points at
+#[derive(Clone, Debug)]
+pub struct Programs {
+    pub degen_launch: Pubkey,
+    pub lending: Pubkey,
+    pub whirlpool: Pubkey,
+}
+
+/// Addresses for one cluster
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub url: String,
+    pub programs: Programs,
+    pub mints: BTreeMap<String, Pubkey>,
+    pub reserves: BTreeMap<String, Pubkey>,
+    pub pools: BTreeMap<String, Pubkey>,
+    pub wallets: BTreeMap<String, Pubkey>,
+}
+
+impl Profile {
+    pub fn mint(&self, name: &str) -> Result<Pubkey> {
+        named(&self.mints, "mint", name)
+    }
+
+    pub fn reserve(&self, name: &str) -> Result<Pubkey> {
+        named(&self.reserves, "reserve", name)
+    }
+
+    pub fn pool(&self, name: &str) -> Result<Pubkey> {
+        named(&self.pools, "pool", name)
+    }
+
+    pub fn wallet(&self, name: &str) -> Result<Pubkey> {
+        named(&self.wallets, "wallet", name)
+    }
+
+    /// A base58 address, or the name of a pool, mint, reserve or wallet in this profile
+    pub fn address(&self, name_or_address: &str) -> Result<Pubkey> {
+        if let Ok(address) = Pubkey::from_str(name_or_address) {
+            return Ok(address);
+        }
+        [&self.pools, &self.mints, &self.reserves, &self.wallets]
+            .into_iter()
+            .find_map(|entries| entries.get(name_or_address).copied())
+            .ok_or_else(|| anyhow!("{} is neither an address nor a name in the address book", name_or_address))
+    }
+}
+
+fn named(entries: &BTreeMap<String, Pubkey>, kind: &str, name: &str) -> Result<Pubkey> {
+    entries
+        .get(name)
+        .copied()
+        .ok_or_else(|| anyhow!("no {} named {} in the address book", kind, name))
+}
+
+/// Named cluster profiles, loaded from a TOML file with one table per cluster
+pub struct AddressBook {
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl AddressBook {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let raw: BTreeMap<String, RawProfile> = toml::from_str(text)?;
+        let profiles = raw
+            .into_iter()
+            .map(|(cluster, profile)| {
+                let profile = profile.parse().with_context(|| format!("profile {}", cluster))?;
+                Ok((cluster, profile))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { profiles })
+    }
+
+    pub fn profile(&self, cluster: &str) -> Result<&Profile> {
+        self.profiles
+            .get(cluster)
+            .ok_or_else(|| anyhow!("no {} profile in the address book", cluster))
+    }
+}
+
+/// The profile name selected by `[provider].cluster` in an Anchor.toml
+pub fn anchor_cluster(anchor_toml: &Path) -> Result<String> {
+    #[derive(Deserialize)]
+    struct AnchorToml {
+        provider: Provider,
+    }
+    #[derive(Deserialize)]
+    struct Provider {
+        cluster: String,
+    }
+
+    let text = fs::read_to_string(anchor_toml).with_context(|| format!("reading {}", anchor_toml.display()))?;
+    let anchor: AnchorToml = toml::from_str(&text).with_context(|| format!("parsing {}", anchor_toml.display()))?;
+    cluster_profile(&anchor.provider.cluster)
+}
+
+/// Maps an Anchor cluster name or RPC URL onto a profile name
+pub fn cluster_profile(cluster: &str) -> Result<String> {
+    let cluster = cluster.to_ascii_lowercase();
+    Ok(match cluster.as_str() {
+        "localnet" | "localhost" => "localnet",
+        "devnet" => "devnet",
+        "mainnet" | "mainnet-beta" => "mainnet",
+        url if url.contains("127.0.0.1") || url.contains("localhost") => "localnet",
+        url if url.contains("devnet") => "devnet",
+        url if url.contains("mainnet") => "mainnet",
+        _ => bail!("no address book profile for cluster {}", cluster),
+    }
+    .to_string())
+}
+
+#[derive(Deserialize)]
+struct RawProfile {
+    url: String,
+    programs: RawPrograms,
+    #[serde(default)]
+    mints: BTreeMap<String, String>,
+    #[serde(default)]
+    reserves: BTreeMap<String, String>,
+    #[serde(default)]
+    pools: BTreeMap<String, String>,
+    #[serde(default)]
+    wallets: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RawPrograms {
+    degen_launch: String,
+    lending: String,
+    whirlpool: String,
+}
+
+impl RawProfile {
+    fn parse(self) -> Result<Profile> {
+        Ok(Profile {
+            url: self.url,
+            programs: Programs {
+                degen_launch: parse_address("programs.degen_launch", &self.programs.degen_launch)?,
+                lending: parse_address("programs.lending", &self.programs.lending)?,
+                whirlpool: parse_address("programs.whirlpool", &self.programs.whirlpool)?,
+            },
+            mints: parse_addresses("mints", self.mints)?,
+            reserves: parse_addresses("reserves", self.reserves)?,
+            pools: parse_addresses("pools", self.pools)?,
+            wallets: parse_addresses("wallets", self.wallets)?,
+        })
+    }
+}
+
+fn parse_address(key: &str, address: &str) -> Result<Pubkey> {
+    Pubkey::from_str(address).map_err(|e| anyhow!("{}: {}: {}", key, address, e))
+}
+
+fn parse_addresses(table: &str, raw: BTreeMap<String, String>) -> Result<BTreeMap<String, Pubkey>> {
+    raw.into_iter()
+        .map(|(name, address)| {
+            let key = format!("{}.{}", table, name);
+            Ok((name, parse_address(&key, &address)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOK: &str = r#"
+        [devnet]
+        url = "https://api.devnet.solana.com"
+
+        [devnet.programs]
+        degen_launch = "6UBFGLf5YBdVAzdzzoMhQsL3pM1KjgRp7EgVDCP4UqGV"
+        lending = "ALend7Ketfx5bxh6ghsCDXAoDrhvEmsXT3cynB6aPLgx"
+        whirlpool = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"
+
+        [devnet.mints]
+        usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+
+        [devnet.pools]
+        degen_usdc = "11111111111111111111111111111112"
+    "#;
+
+    fn pubkey(address: &str) -> Pubkey {
+        Pubkey::from_str(address).unwrap()
+    }
+
+    #[test]
+    fn parses_profiles() {
+        let book = AddressBook::parse(BOOK).unwrap();
+        let devnet = book.profile("devnet").unwrap();
+
+        assert_eq!(devnet.url, "https://api.devnet.solana.com");
+        assert_eq!(devnet.programs.lending, pubkey("ALend7Ketfx5bxh6ghsCDXAoDrhvEmsXT3cynB6aPLgx"));
+        assert_eq!(devnet.programs.whirlpool, pubkey("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"));
+        assert_eq!(devnet.mint("usdc").unwrap(), pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"));
+        // Missing tables default to empty
+        assert!(devnet.reserves.is_empty() && devnet.wallets.is_empty());
+        assert!(devnet.reserve("usdc").is_err());
+        assert!(book.profile("mainnet").is_err());
+    }
+
+    #[test]
+    fn resolves_names_and_addresses() {
+        let book = AddressBook::parse(BOOK).unwrap();
+        let devnet = book.profile("devnet").unwrap();
+
+        assert_eq!(devnet.address("degen_usdc").unwrap(), pubkey("11111111111111111111111111111112"));
+        assert_eq!(devnet.address("usdc").unwrap(), devnet.mint("usdc").unwrap());
+        let address = Pubkey::new_unique();
+        assert_eq!(devnet.address(&address.to_string()).unwrap(), address);
+        assert!(devnet.address("sol_usdc").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        let bad = BOOK.replace("11111111111111111111111111111112", "not-an-address");
+        let err = AddressBook::parse(&bad).err().unwrap();
+        assert!(format!("{:#}", err).contains("pools.degen_usdc"));
+
+        let missing_program = BOOK.replace("lending = ", "lender = ");
+        assert!(AddressBook::parse(&missing_program).is_err());
+    }
+
+    #[test]
+    fn loads_the_repository_address_book() {
+        let book = AddressBook::parse(include_str!("../../../addresses.toml")).unwrap();
+        let whirlpool = pubkey("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+        for cluster in ["localnet", "devnet", "mainnet"] {
+            let profile = book.profile(cluster).unwrap();
+            assert_eq!(profile.programs.degen_launch, degen_launch::ID, "{}", cluster);
+            assert_eq!(profile.programs.whirlpool, whirlpool, "{}", cluster);
+            assert_eq!(cluster_profile(&profile.url).unwrap(), cluster);
+        }
+
+        let mainnet = book.profile("mainnet").unwrap();
+        assert_eq!(mainnet.reserve("usdc").unwrap(), pubkey("BgxfHJDzm44T7XG68MYKx7YisTjZu73tVovyZSjJMpmw"));
+        assert_eq!(mainnet.pool("sol_usdc_64").unwrap(), pubkey("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ"));
+
+        // Every reserve has to be one the profile's lender owns, or flash borrows fail
+        let solend = pubkey("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+        let solend_main_market_reserves = [
+            pubkey("BgxfHJDzm44T7XG68MYKx7YisTjZu73tVovyZSjJMpmw"), // USDC
+            pubkey("8PbodeaosQP19SjYFx855UMqWxH2HynZLdBXmsrbac36"), // SOL
+        ];
+        assert_eq!(mainnet.programs.lending, solend);
+        for (name, reserve) in &mainnet.reserves {
+            assert!(
+                solend_main_market_reserves.contains(reserve),
+                "mainnet reserve {} is not a Solend main market reserve",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn maps_clusters_to_profiles() {
+        assert_eq!(cluster_profile("Localnet").unwrap(), "localnet");
+        assert_eq!(cluster_profile("mainnet-beta").unwrap(), "mainnet");
+        assert_eq!(cluster_profile("devnet").unwrap(), "devnet");
+        assert_eq!(cluster_profile("http://127.0.0.1:8899").unwrap(), "localnet");
+        assert_eq!(cluster_profile("https://api.devnet.solana.com").unwrap(), "devnet");
+        assert_eq!(cluster_profile("https://example.mainnet.rpcpool.com").unwrap(), "mainnet");
+        assert!(cluster_profile("testnet").is_err());
+    }
+}
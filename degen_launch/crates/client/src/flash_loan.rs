@@ -0,0 +1,49 @@
+use degen_launch::solend;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::accounts::LenderAccounts;
+
+/// A strategy instruction with the lender's flash borrow right before it and the
+/// flash repay right after. The three have to stay adjacent top-level instructions,
+/// and the repay names the borrow by its index in the transaction, so they are
+/// only turned into instructions once that index is known.
+#[derive(Clone, Debug)]
+pub struct FlashLoan {
+    pub lender: LenderAccounts,
+    /// Vault the loan is paid into and repaid from
+    pub loan_account: Pubkey,
+    pub amount: u64,
+    /// Signs the repay, as the delegate the strategy approves over the loan account
+    pub operator: Pubkey,
+    pub strategy: Instruction,
+}
+
+impl FlashLoan {
+    /// Borrow, strategy and repay, with the borrow at `borrow_index` in the transaction
+    pub fn instructions(&self, borrow_index: u8) -> [Instruction; 3] {
+        let lender = &self.lender;
+        let borrow = solend::flash_borrow_reserve_liquidity_ix(
+            lender.lending_program,
+            self.amount,
+            lender.reserve_liquidity_supply,
+            self.loan_account,
+            lender.reserve,
+            lender.lending_market,
+            lender.lending_market_authority,
+        );
+        // The reserve's own fee receiver doubles as the host, so the whole fee goes to the reserve
+        let repay = solend::flash_repay_reserve_liquidity_ix(
+            lender.lending_program,
+            self.amount,
+            borrow_index,
+            self.loan_account,
+            lender.reserve_liquidity_supply,
+            lender.fee_receiver,
+            lender.fee_receiver,
+            lender.reserve,
+            lender.lending_market,
+            self.operator,
+        );
+        [borrow, self.strategy.clone(), repay]
+    }
+}
@@ -0,0 +1,543 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::{bail, Result};
+use degen_launch::{AmountMode, ConfigParams, FailureKind, Role, RouteStep, Strategy, Venue, MAX_ROUTE_STEPS};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::accounts::LenderAccounts;
+use crate::flash_loan::FlashLoan;
+use crate::pda;
+use crate::quote;
+use crate::resolve::{ResolvedWhirlpool, Resolver};
+
+fn instruction(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: degen_launch::ID,
+        accounts,
+        data,
+    }
+}
+
+/// Builds degen_launch instructions with every account resolved, signed by `authority`
+/// (the admin, operator or treasurer, depending on the instruction)
+pub struct InstructionBuilder<'a> {
+    resolver: Resolver<'a>,
+    authority: Pubkey,
+}
+
+impl<'a> InstructionBuilder<'a> {
+    pub fn new(resolver: Resolver<'a>, authority: Pubkey) -> Self {
+        Self { resolver, authority }
+    }
+
+    pub fn resolver(&self) -> &Resolver<'a> {
+        &self.resolver
+    }
+
+    // Admin
+
+    pub fn initialize_config(&self, params: ConfigParams) -> Instruction {
+        instruction(
+            degen_launch::accounts::InitializeConfig {
+                config: pda::config(),
+                admin: self.authority,
+                program: degen_launch::ID,
+                program_data: pda::program_data(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::InitializeConfig { params }.data(),
+        )
+    }
+
+    pub fn update_config(&self, params: ConfigParams, new_admin: Option<Pubkey>) -> Instruction {
+        instruction(
+            degen_launch::accounts::UpdateConfig {
+                config: pda::config(),
+                admin: self.authority,
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::UpdateConfig { params, new_admin }.data(),
+        )
+    }
+
+    pub fn initialize_vault(&self, mint: &Pubkey, min_working_balance: u64) -> Instruction {
+        instruction(
+            degen_launch::accounts::InitializeVault {
+                config: pda::config(),
+                admin: self.authority,
+                vault_authority: pda::vault_authority(),
+                mint: *mint,
+                vault: pda::vault(mint),
+                ledger: pda::vault_ledger(mint),
+                token_program: pda::TOKEN_PROGRAM_ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::InitializeVault { min_working_balance }.data(),
+        )
+    }
+
+    pub fn set_min_working_balance(&self, mint: &Pubkey, min_working_balance: u64) -> Instruction {
+        instruction(
+            degen_launch::accounts::SetMinWorkingBalance {
+                config: pda::config(),
+                admin: self.authority,
+                ledger: pda::vault_ledger(mint),
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::SetMinWorkingBalance { min_working_balance }.data(),
+        )
+    }
+
+    pub fn pause(&self) -> Instruction {
+        instruction(self.set_paused_accounts(), degen_launch::instruction::Pause {}.data())
+    }
+
+    pub fn unpause(&self) -> Instruction {
+        instruction(self.set_paused_accounts(), degen_launch::instruction::Unpause {}.data())
+    }
+
+    fn set_paused_accounts(&self) -> Vec<AccountMeta> {
+        degen_launch::accounts::SetPaused {
+            config: pda::config(),
+            admin: self.authority,
+        }
+        .to_account_metas(None)
+    }
+
+    pub fn add_role(&self, role: Role, member: Pubkey) -> Instruction {
+        instruction(
+            self.manage_role_accounts(),
+            degen_launch::instruction::AddRole { role, member }.data(),
+        )
+    }
+
+    pub fn remove_role(&self, role: Role, member: Pubkey) -> Instruction {
+        instruction(
+            self.manage_role_accounts(),
+            degen_launch::instruction::RemoveRole { role, member }.data(),
+        )
+    }
+
+    fn manage_role_accounts(&self) -> Vec<AccountMeta> {
+        degen_launch::accounts::ManageRole {
+            config: pda::config(),
+            admin: self.authority,
+        }
+        .to_account_metas(None)
+    }
+
+    pub fn initialize_strategy_stats(&self, strategy: Strategy, pool: Pubkey) -> Instruction {
+        instruction(
+            degen_launch::accounts::InitializeStrategyStats {
+                config: pda::config(),
+                admin: self.authority,
+                stats: pda::strategy_stats(strategy, &pool),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::InitializeStrategyStats { strategy, pool }.data(),
+        )
+    }
+
+    // Treasurer
+
+    /// Moves `amount` of `mint` from its vault to the treasury's associated token account
+    pub fn withdraw(&self, mint: &Pubkey, amount: u64) -> Result<Instruction> {
+        Ok(instruction(
+            self.treasury_transfer_accounts(mint)?,
+            degen_launch::instruction::Withdraw { amount }.data(),
+        ))
+    }
+
+    /// Moves everything above the vault's minimum working balance to the treasury
+    pub fn sweep_profits(&self, mint: &Pubkey) -> Result<Instruction> {
+        Ok(instruction(
+            self.treasury_transfer_accounts(mint)?,
+            degen_launch::instruction::SweepProfits {}.data(),
+        ))
+    }
+
+    fn treasury_transfer_accounts(&self, mint: &Pubkey) -> Result<Vec<AccountMeta>> {
+        let config = self.resolver.config()?;
+        Ok(degen_launch::accounts::TreasuryTransfer {
+            config: pda::config(),
+            treasurer: self.authority,
+            vault_authority: pda::vault_authority(),
+            ledger: pda::vault_ledger(mint),
+            vault: pda::vault(mint),
+            treasury_token_account: pda::associated_token_address(&config.treasury, mint),
+            token_program: pda::TOKEN_PROGRAM_ID,
+        }
+        .to_account_metas(None))
+    }
+
+    // Operator
+
+    pub fn record_failure(&self, strategy: Strategy, pool: &Pubkey, kind: FailureKind) -> Instruction {
+        instruction(
+            degen_launch::accounts::RecordFailure {
+                config: pda::config(),
+                operator: self.authority,
+                stats: pda::strategy_stats(strategy, pool),
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::RecordFailure { kind }.data(),
+        )
+    }
+
+    pub fn close_execution_receipt(&self, nonce: u64) -> Instruction {
+        instruction(
+            degen_launch::accounts::CloseExecutionReceipt {
+                receipt: pda::execution_receipt(&self.authority, nonce),
+                operator: self.authority,
+            }
+            .to_account_metas(None),
+            degen_launch::instruction::CloseExecutionReceipt {}.data(),
+        )
+    }
+
+    /// Borrows `amount` of token B of `pool` and dumps token A into the pool
+    /// inside the loan, accepting `slippage_bps` below the spot quote.
+    /// A receipt is written when `receipt_nonce` is given.
+    pub fn execute_flashloan_selfdump(
+        &self,
+        pool: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+        receipt_nonce: Option<u64>,
+    ) -> Result<FlashLoan> {
+        let config = self.resolver.config()?;
+        let pool = self.resolver.whirlpool(pool, true)?;
+        let mint = pool.accounts.token_mint_b;
+        let lender = self.resolver.lender_for_mint(&config.lending_program, &mint, &config.allowed_reserves)?;
+        let minimum_amount_out =
+            quote::min_amount_out(quote::whirlpool_spot_quote(&pool, amount, true), slippage_bps);
+
+        let mut accounts = self.selfdump_accounts(&pool, &lender, receipt_nonce);
+        accounts.extend(profit_accounts(&config, &self.authority, &mint));
+
+        let strategy = instruction(
+            accounts,
+            degen_launch::instruction::ExecuteFlashloanSelfdump {
+                amount,
+                minimum_amount_out,
+                bumps: pool.bumps,
+                receipt_nonce: receipt_nonce.unwrap_or_default(),
+            }
+            .data(),
+        );
+        Ok(self.flash_loan(lender, &mint, amount, strategy))
+    }
+
+    /// `strategy` between the lender's borrow of `amount` of `mint` into its vault and
+    /// the repay the operator signs
+    fn flash_loan(&self, lender: LenderAccounts, mint: &Pubkey, amount: u64, strategy: Instruction) -> FlashLoan {
+        FlashLoan {
+            lender,
+            loan_account: pda::vault(mint),
+            amount,
+            operator: self.authority,
+            strategy,
+        }
+    }
+
+    fn selfdump_accounts(
+        &self,
+        pool: &ResolvedWhirlpool,
+        lender: &LenderAccounts,
+        receipt_nonce: Option<u64>,
+    ) -> Vec<AccountMeta> {
+        degen_launch::accounts::ExecuteFlashloanSelfdump {
+            config: pda::config(),
+            operator: self.authority,
+            reserve: lender.reserve,
+            reserve_liquidity_supply: lender.reserve_liquidity_supply,
+            instructions: sysvar::instructions::ID,
+            user_liquidity: pda::vault(&pool.accounts.token_mint_b),
+            whirlpool_program: pool.program,
+            vault_authority: pda::vault_authority(),
+            token_owner_account: pda::vault(&pool.accounts.token_mint_a),
+            token_vault_a: pool.accounts.token_vault_a,
+            token_vault_b: pool.accounts.token_vault_b,
+            token_mint_a: pool.accounts.token_mint_a,
+            token_mint_b: pool.accounts.token_mint_b,
+            whirlpool: pool.accounts.whirlpool,
+            tick_array_0: pool.accounts.tick_arrays[0],
+            oracle: pool.accounts.oracle,
+            token_program: pda::TOKEN_PROGRAM_ID,
+            fee_tier: pool.fee_tier,
+            tick_array_1: Some(pool.accounts.tick_arrays[1]),
+            tick_array_2: Some(pool.accounts.tick_arrays[2]),
+            stats: pda::strategy_stats(Strategy::Selfdump, &pool.accounts.whirlpool),
+            receipt: receipt_nonce.map(|nonce| pda::execution_receipt(&self.authority, nonce)),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    /// Swaps `amount_in` of `input_mint` on `pool` between the program vaults
+    pub fn swap_via_venue(
+        &self,
+        pool: &Pubkey,
+        input_mint: &Pubkey,
+        amount_in: u64,
+        slippage_bps: u16,
+    ) -> Result<Instruction> {
+        let (pool, a_to_b) = self.resolver.whirlpool_for_input(pool, input_mint)?;
+        let minimum_amount_out =
+            quote::min_amount_out(quote::whirlpool_spot_quote(&pool, amount_in, a_to_b), slippage_bps);
+
+        let mut accounts = degen_launch::accounts::SwapViaVenue {
+            config: pda::config(),
+            operator: self.authority,
+            vault_authority: pda::vault_authority(),
+            token_program: pda::TOKEN_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(orca_venue_accounts(&pool));
+
+        Ok(instruction(
+            accounts,
+            degen_launch::instruction::SwapViaVenue {
+                venue: Venue::Orca,
+                amount_in,
+                minimum_amount_out,
+                a_to_b,
+            }
+            .data(),
+        ))
+    }
+
+    /// Borrows `amount` of `mint` and routes it through the Orca `pools`, each leg
+    /// spending everything the previous one produced and the last one returning to
+    /// `mint`, with `slippage_bps` on every leg. The route pays for the loan's repay.
+    pub fn execute_route(
+        &self,
+        mint: &Pubkey,
+        pools: &[Pubkey],
+        amount: u64,
+        slippage_bps: u16,
+        min_profit: u64,
+    ) -> Result<FlashLoan> {
+        if pools.is_empty() || pools.len() > MAX_ROUTE_STEPS {
+            bail!("a route takes 1 to {} pools", MAX_ROUTE_STEPS);
+        }
+        let config = self.resolver.config()?;
+        let lender = self.resolver.lender_for_mint(&config.lending_program, mint, &config.allowed_reserves)?;
+
+        let mut steps = Vec::with_capacity(pools.len());
+        let mut leg_accounts = Vec::new();
+        let mut input_mint = *mint;
+        let mut expected_in = amount;
+        for (i, pool) in pools.iter().enumerate() {
+            let (pool, a_to_b) = self.resolver.whirlpool_for_input(pool, &input_mint)?;
+            let quoted = quote::whirlpool_spot_quote(&pool, expected_in, a_to_b);
+            steps.push(RouteStep {
+                venue: Venue::Orca,
+                a_to_b,
+                amount_mode: if i == 0 {
+                    AmountMode::Exact(amount)
+                } else {
+                    AmountMode::PreviousOutput
+                },
+                min_amount_out: quote::min_amount_out(quoted, slippage_bps),
+            });
+            leg_accounts.extend(orca_venue_accounts(&pool));
+            input_mint = if a_to_b { pool.accounts.token_mint_b } else { pool.accounts.token_mint_a };
+            expected_in = quoted;
+        }
+        if input_mint != *mint {
+            bail!("pools do not route back to {}", mint);
+        }
+
+        let mut accounts = degen_launch::accounts::ExecuteRoute {
+            config: pda::config(),
+            operator: self.authority,
+            vault_authority: pda::vault_authority(),
+            token_program: pda::TOKEN_PROGRAM_ID,
+            loan_account: pda::vault(mint),
+            reserve: lender.reserve,
+            reserve_liquidity_supply: lender.reserve_liquidity_supply,
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(leg_accounts);
+        accounts.extend(profit_accounts(&config, &self.authority, mint));
+
+        let strategy = instruction(
+            accounts,
+            degen_launch::instruction::ExecuteRoute { amount, steps, min_profit }.data(),
+        );
+        Ok(self.flash_loan(lender, mint, amount, strategy))
+    }
+
+    /// Borrows `amount` of `usdc_mint`, buys `degen_mint` with it on `pool_a` and sells
+    /// it back on `pool_b`, with `slippage_bps` on both legs. The proceeds pay the
+    /// loan and the reserve's fee back.
+    /// A receipt is written when `receipt_nonce` is given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_round_trip_arbitrage(
+        &self,
+        usdc_mint: &Pubkey,
+        degen_mint: &Pubkey,
+        pool_a: &Pubkey,
+        pool_b: &Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+        min_profit: u64,
+        receipt_nonce: Option<u64>,
+    ) -> Result<FlashLoan> {
+        let config = self.resolver.config()?;
+        let (pool_a, a_to_b) = self.resolver.whirlpool_for_input(pool_a, usdc_mint)?;
+        let degen_quote = quote::whirlpool_spot_quote(&pool_a, amount, a_to_b);
+        let (pool_b, b_to_a) = self.resolver.whirlpool_for_input(pool_b, degen_mint)?;
+        let usdc_quote = quote::whirlpool_spot_quote(&pool_b, degen_quote, b_to_a);
+        let lender = self.resolver.lender_for_mint(&config.lending_program, usdc_mint, &config.allowed_reserves)?;
+
+        let mut accounts = degen_launch::accounts::ExecuteRoundTripArbitrage {
+            config: pda::config(),
+            operator: self.authority,
+            vault_authority: pda::vault_authority(),
+            whirlpool_program: self.resolver.whirlpool_program(),
+            token_program: pda::TOKEN_PROGRAM_ID,
+            usdc_account: pda::vault(usdc_mint),
+            degen_account: pda::vault(degen_mint),
+            reserve: lender.reserve,
+            reserve_liquidity_supply: lender.reserve_liquidity_supply,
+            instructions: sysvar::instructions::ID,
+            pool_a: orca_pool(&pool_a),
+            pool_b: orca_pool(&pool_b),
+            stats: pda::strategy_stats(Strategy::RoundTrip, &pool_a.accounts.whirlpool),
+            receipt: receipt_nonce.map(|nonce| pda::execution_receipt(&self.authority, nonce)),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(profit_accounts(&config, &self.authority, usdc_mint));
+
+        let strategy = instruction(
+            accounts,
+            degen_launch::instruction::ExecuteRoundTripArbitrage {
+                amount,
+                min_degen_out: quote::min_amount_out(degen_quote, slippage_bps),
+                min_usdc_out: quote::min_amount_out(usdc_quote, slippage_bps),
+                min_profit,
+                receipt_nonce: receipt_nonce.unwrap_or_default(),
+                bumps: [pool_a.bumps, pool_b.bumps],
+            }
+            .data(),
+        );
+        Ok(self.flash_loan(lender, usdc_mint, amount, strategy))
+    }
+
+    /// Borrows `amount` of `usdc_mint` and cycles it through `pools`, each trading the
+    /// previous pool's output. The proceeds pay the loan and the reserve's fee back.
+    /// A receipt is written when `receipt_nonce` is given.
+    pub fn execute_triangular_arbitrage(
+        &self,
+        usdc_mint: &Pubkey,
+        pools: [Pubkey; 3],
+        amount: u64,
+        min_profit: u64,
+        receipt_nonce: Option<u64>,
+    ) -> Result<FlashLoan> {
+        let config = self.resolver.config()?;
+
+        let mut resolved = Vec::with_capacity(3);
+        let mut input_mint = *usdc_mint;
+        for pool in &pools {
+            let (pool, a_to_b) = self.resolver.whirlpool_for_input(pool, &input_mint)?;
+            input_mint = if a_to_b { pool.accounts.token_mint_b } else { pool.accounts.token_mint_a };
+            resolved.push(pool);
+        }
+        if input_mint != *usdc_mint {
+            bail!("pools do not cycle back to {}", usdc_mint);
+        }
+        let hop_mint = |pool: &ResolvedWhirlpool, input: &Pubkey| {
+            if pool.accounts.token_mint_a == *input {
+                pool.accounts.token_mint_b
+            } else {
+                pool.accounts.token_mint_a
+            }
+        };
+        let hop_1 = hop_mint(&resolved[0], usdc_mint);
+        let hop_2 = hop_mint(&resolved[1], &hop_1);
+        let lender = self.resolver.lender_for_mint(&config.lending_program, usdc_mint, &config.allowed_reserves)?;
+
+        let mut accounts = degen_launch::accounts::ExecuteTriangularArbitrage {
+            config: pda::config(),
+            operator: self.authority,
+            vault_authority: pda::vault_authority(),
+            whirlpool_program: self.resolver.whirlpool_program(),
+            token_program: pda::TOKEN_PROGRAM_ID,
+            usdc_account: pda::vault(usdc_mint),
+            hop_1_account: pda::vault(&hop_1),
+            hop_2_account: pda::vault(&hop_2),
+            reserve: lender.reserve,
+            reserve_liquidity_supply: lender.reserve_liquidity_supply,
+            instructions: sysvar::instructions::ID,
+            pool_1: orca_pool(&resolved[0]),
+            pool_2: orca_pool(&resolved[1]),
+            pool_3: orca_pool(&resolved[2]),
+            stats: pda::strategy_stats(Strategy::Triangular, &pools[0]),
+            receipt: receipt_nonce.map(|nonce| pda::execution_receipt(&self.authority, nonce)),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(profit_accounts(&config, &self.authority, usdc_mint));
+
+        let strategy = instruction(
+            accounts,
+            degen_launch::instruction::ExecuteTriangularArbitrage {
+                amount,
+                min_profit,
+                receipt_nonce: receipt_nonce.unwrap_or_default(),
+                bumps: [resolved[0].bumps, resolved[1].bumps, resolved[2].bumps],
+            }
+            .data(),
+        );
+        Ok(self.flash_loan(lender, usdc_mint, amount, strategy))
+    }
+}
+
+fn orca_pool(pool: &ResolvedWhirlpool) -> degen_launch::accounts::OrcaPool {
+    degen_launch::accounts::OrcaPool {
+        whirlpool: pool.accounts.whirlpool,
+        token_vault_a: pool.accounts.token_vault_a,
+        token_vault_b: pool.accounts.token_vault_b,
+        tick_array_0: pool.accounts.tick_arrays[0],
+        tick_array_1: pool.accounts.tick_arrays[1],
+        tick_array_2: pool.accounts.tick_arrays[2],
+        oracle: pool.accounts.oracle,
+    }
+}
+
+/// Remaining accounts of an Orca leg, in the order the program's OrcaVenue loads them
+fn orca_venue_accounts(pool: &ResolvedWhirlpool) -> Vec<AccountMeta> {
+    let p = &pool.accounts;
+    vec![
+        AccountMeta::new_readonly(pool.program, false),
+        AccountMeta::new(p.whirlpool, false),
+        AccountMeta::new(pda::vault(&p.token_mint_a), false),
+        AccountMeta::new(p.token_vault_a, false),
+        AccountMeta::new(pda::vault(&p.token_mint_b), false),
+        AccountMeta::new(p.token_vault_b, false),
+        AccountMeta::new(p.tick_arrays[0], false),
+        AccountMeta::new(p.tick_arrays[1], false),
+        AccountMeta::new(p.tick_arrays[2], false),
+        AccountMeta::new_readonly(p.oracle, false),
+    ]
+}
+
+/// Profit split recipients: the operator's associated token account, then each beneficiary's
+fn profit_accounts(config: &degen_launch::Config, operator: &Pubkey, mint: &Pubkey) -> Vec<AccountMeta> {
+    std::iter::once(operator)
+        .chain(config.beneficiaries.iter().map(|b| &b.wallet))
+        .map(|wallet| AccountMeta::new(pda::associated_token_address(wallet, mint), false))
+        .collect()
+}
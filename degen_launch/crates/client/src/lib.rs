@@ -0,0 +1,19 @@
+pub mod accounts;
+pub mod address_book;
+pub mod flash_loan;
+pub mod instructions;
+pub mod lookup_table;
+pub mod pda;
+pub mod quote;
+pub mod resolve;
+pub mod simulate;
+pub mod transaction;
+
+pub use accounts::{LenderAccounts, WhirlpoolAccounts};
+pub use address_book::{AddressBook, Profile};
+pub use flash_loan::FlashLoan;
+pub use instructions::InstructionBuilder;
+pub use lookup_table::LookupTableManager;
+pub use resolve::{ResolvedWhirlpool, Resolver};
+pub use simulate::{SimulationReport, Simulator};
+pub use transaction::TransactionBuilder;
@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        state::AddressLookupTable,
+        AddressLookupTableAccount,
+    },
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use std::collections::HashSet;
+
+use crate::accounts::{LenderAccounts, WhirlpoolAccounts};
+use crate::pda::TOKEN_PROGRAM_ID;
+
+// Keeps each extend transaction under the packet size limit
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Accounts every flash loan run over `pools` touches regardless of amounts:
+/// the programs and sysvars, the lender's reserve accounts and each pool's vaults
+/// and tick arrays
+pub fn static_accounts(
+    lender: &LenderAccounts,
+    whirlpool_program: &Pubkey,
+    pools: &[WhirlpoolAccounts],
+) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        degen_launch::ID,
+        *whirlpool_program,
+        TOKEN_PROGRAM_ID,
+        solana_sdk::system_program::ID,
+        solana_sdk::sysvar::instructions::ID,
+    ];
+    addresses.extend(lender.addresses());
+    for pool in pools {
+        addresses.extend(pool.addresses());
+    }
+    dedup(addresses)
+}
+
+fn dedup(addresses: Vec<Pubkey>) -> Vec<Pubkey> {
+    let mut seen = HashSet::new();
+    addresses.into_iter().filter(|a| seen.insert(*a)).collect()
+}
+
+/// Creates, extends and loads address lookup tables owned by `authority`
+pub struct LookupTableManager<'a> {
+    rpc: &'a RpcClient,
+    authority: &'a Keypair,
+}
+
+impl<'a> LookupTableManager<'a> {
+    pub fn new(rpc: &'a RpcClient, authority: &'a Keypair) -> Self {
+        Self { rpc, authority }
+    }
+
+    /// Creates a table holding `addresses` and returns its address.
+    /// The table becomes usable one slot after its last extension.
+    pub fn create(&self, addresses: &[Pubkey]) -> Result<Pubkey> {
+        let recent_slot = self.rpc.get_slot_with_commitment(CommitmentConfig::finalized())?;
+        let (create_ix, table) = create_lookup_table(
+            self.authority.pubkey(),
+            self.authority.pubkey(),
+            recent_slot,
+        );
+        self.send(&[create_ix])?;
+        self.extend(&table, addresses)?;
+        Ok(table)
+    }
+
+    /// Adds whichever of `addresses` the table does not hold yet. Returns how many were added.
+    pub fn extend(&self, table: &Pubkey, addresses: &[Pubkey]) -> Result<usize> {
+        let existing: HashSet<Pubkey> = self.load(table)?.addresses.into_iter().collect();
+        let missing: Vec<Pubkey> = dedup(addresses.to_vec())
+            .into_iter()
+            .filter(|a| !existing.contains(a))
+            .collect();
+
+        for chunk in missing.chunks(MAX_ADDRESSES_PER_EXTEND) {
+            let ix = extend_lookup_table(
+                *table,
+                self.authority.pubkey(),
+                Some(self.authority.pubkey()),
+                chunk.to_vec(),
+            );
+            self.send(&[ix])?;
+        }
+
+        Ok(missing.len())
+    }
+
+    pub fn load(&self, table: &Pubkey) -> Result<AddressLookupTableAccount> {
+        let account = self.rpc.get_account(table)?;
+        let state = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| anyhow!("{} is not a lookup table: {}", table, e))?;
+        Ok(AddressLookupTableAccount {
+            key: *table,
+            addresses: state.addresses.to_vec(),
+        })
+    }
+
+    fn send(&self, instructions: &[solana_sdk::instruction::Instruction]) -> Result<()> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.authority.pubkey()),
+            &[self.authority],
+            blockhash,
+        );
+        self.rpc.send_and_confirm_transaction(&tx)?;
+        Ok(())
+    }
+}
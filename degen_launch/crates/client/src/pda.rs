@@ -0,0 +1,76 @@
+use degen_launch::{
+    Strategy, CONFIG_SEED, EXECUTION_RECEIPT_SEED, STRATEGY_STATS_SEED, VAULT_AUTHORITY_SEED, VAULT_LEDGER_SEED,
+    VAULT_SEED,
+};
+use solana_sdk::pubkey::Pubkey;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+fn program_address(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &degen_launch::ID).0
+}
+
+pub fn config() -> Pubkey {
+    program_address(&[CONFIG_SEED])
+}
+
+/// Upgradeable loader account holding the program's upgrade authority
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[degen_launch::ID.as_ref()], &solana_sdk::bpf_loader_upgradeable::ID).0
+}
+
+pub fn vault_authority() -> Pubkey {
+    program_address(&[VAULT_AUTHORITY_SEED])
+}
+
+pub fn vault(mint: &Pubkey) -> Pubkey {
+    program_address(&[VAULT_SEED, mint.as_ref()])
+}
+
+pub fn vault_ledger(mint: &Pubkey) -> Pubkey {
+    program_address(&[VAULT_LEDGER_SEED, mint.as_ref()])
+}
+
+pub fn strategy_stats(strategy: Strategy, pool: &Pubkey) -> Pubkey {
+    program_address(&[STRATEGY_STATS_SEED, strategy.seed(), pool.as_ref()])
+}
+
+pub fn execution_receipt(operator: &Pubkey, nonce: u64) -> Pubkey {
+    program_address(&[EXECUTION_RECEIPT_SEED, operator.as_ref(), &nonce.to_le_bytes()])
+}
+
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// SPL token-lending market authority, which signs for the market's reserves
+pub fn lending_market_authority(lending_program: &Pubkey, lending_market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[lending_market.as_ref()], lending_program).0
+}
+
+/// Whirlpool oracle and its bump
+pub fn whirlpool_oracle(whirlpool_program: &Pubkey, whirlpool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"oracle", whirlpool.as_ref()], whirlpool_program)
+}
+
+/// Tick array starting at `start_tick_index` and its bump
+pub fn whirlpool_tick_array(whirlpool_program: &Pubkey, whirlpool: &Pubkey, start_tick_index: i32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start_tick_index.to_string().as_bytes()],
+        whirlpool_program,
+    )
+}
+
+pub fn whirlpool_fee_tier(whirlpool_program: &Pubkey, whirlpools_config: &Pubkey, tick_spacing: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"fee_tier", whirlpools_config.as_ref(), &tick_spacing.to_le_bytes()],
+        whirlpool_program,
+    )
+    .0
+}
@@ -0,0 +1,45 @@
+use crate::resolve::ResolvedWhirlpool;
+
+/// Fee rates are in hundredths of a basis point
+const FEE_RATE_DENOMINATOR: f64 = 1_000_000.0;
+
+/// Output of swapping `amount_in` at the pool's current price after its fee.
+/// Ignores price impact, so only good for amounts small against the pool's liquidity.
+pub fn whirlpool_spot_quote(pool: &ResolvedWhirlpool, amount_in: u64, a_to_b: bool) -> u64 {
+    let sqrt_price = pool.sqrt_price as f64 / 2f64.powi(64);
+    // Price of A in B, in native units
+    let price = sqrt_price * sqrt_price;
+    let after_fee = amount_in as f64 * (1.0 - pool.fee_rate as f64 / FEE_RATE_DENOMINATOR);
+    let out = if a_to_b { after_fee * price } else { after_fee / price };
+    out.floor() as u64
+}
+
+/// Lowest acceptable output for `quote` with `slippage_bps` of tolerance
+pub fn min_amount_out(quote: u64, slippage_bps: u16) -> u64 {
+    (quote as u128 * (10_000 - slippage_bps.min(10_000)) as u128 / 10_000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_amount_out_applies_slippage() {
+        assert_eq!(min_amount_out(1_000_000, 0), 1_000_000);
+        assert_eq!(min_amount_out(1_000_000, 50), 995_000);
+        // Rounds down so the floor is never above the tolerance
+        assert_eq!(min_amount_out(999, 100), 989);
+    }
+
+    #[test]
+    fn min_amount_out_caps_slippage() {
+        assert_eq!(min_amount_out(1_000_000, 10_000), 0);
+        assert_eq!(min_amount_out(1_000_000, u16::MAX), 0);
+    }
+
+    #[test]
+    fn min_amount_out_does_not_overflow() {
+        assert_eq!(min_amount_out(u64::MAX, 0), u64::MAX);
+        assert_eq!(min_amount_out(u64::MAX, 5_000), u64::MAX / 2);
+    }
+}
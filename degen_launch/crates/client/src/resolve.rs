@@ -0,0 +1,169 @@
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, bail, Result};
+use degen_launch::{solend::ReserveInfo, whirlpool_utils, Config, WhirlpoolBumps};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::{LenderAccounts, WhirlpoolAccounts};
+use crate::pda;
+
+// SPL token-lending Reserve layout, shared by Solend; the program parses the rest
+const RESERVE_LIQUIDITY_MINT_OFFSET: usize = 42;
+
+// Whirlpool layout beyond what the program itself reads
+const WHIRLPOOL_FEE_RATE_OFFSET: usize = 45;
+const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+
+/// A whirlpool with everything needed to swap on it in one direction
+#[derive(Clone, Copy, Debug)]
+pub struct ResolvedWhirlpool {
+    /// Whirlpool program the pool belongs to
+    pub program: Pubkey,
+    pub accounts: WhirlpoolAccounts,
+    pub whirlpools_config: Pubkey,
+    pub fee_tier: Pubkey,
+    pub tick_spacing: u16,
+    /// Oracle and tick array bumps, for instructions that verify them cheaply
+    pub bumps: WhirlpoolBumps,
+    /// Fee in hundredths of a basis point
+    pub fee_rate: u16,
+    pub sqrt_price: u128,
+}
+
+/// Looks up on-chain state to fill in the accounts instructions need
+pub struct Resolver<'a> {
+    rpc: &'a RpcClient,
+    whirlpool_program: Pubkey,
+}
+
+impl<'a> Resolver<'a> {
+    /// `whirlpool_program` is the cluster's Whirlpool program, from the address book
+    pub fn new(rpc: &'a RpcClient, whirlpool_program: Pubkey) -> Self {
+        Self { rpc, whirlpool_program }
+    }
+
+    pub fn whirlpool_program(&self) -> Pubkey {
+        self.whirlpool_program
+    }
+
+    pub fn config(&self) -> Result<Config> {
+        let data = self.rpc.get_account_data(&pda::config())?;
+        Ok(Config::try_deserialize(&mut data.as_slice())?)
+    }
+
+    /// Reads `whirlpool` and derives the oracle and the tick arrays a swap walks
+    pub fn whirlpool(&self, whirlpool: &Pubkey, a_to_b: bool) -> Result<ResolvedWhirlpool> {
+        let data = self.rpc.get_account_data(whirlpool)?;
+        resolve_whirlpool(&self.whirlpool_program, whirlpool, &data, a_to_b)
+    }
+
+    /// Like `whirlpool`, picking the direction that spends `input_mint`.
+    /// Returns the pool and whether that direction is A to B.
+    pub fn whirlpool_for_input(&self, whirlpool: &Pubkey, input_mint: &Pubkey) -> Result<(ResolvedWhirlpool, bool)> {
+        let data = self.rpc.get_account_data(whirlpool)?;
+        let tokens = whirlpool_utils::parse_tokens_from_whirlpool(&data)
+            .map_err(|e| anyhow!("{}: {}", whirlpool, e))?;
+        let a_to_b = if *input_mint == tokens.token_mint_a {
+            true
+        } else if *input_mint == tokens.token_mint_b {
+            false
+        } else {
+            bail!("{} does not trade {}", whirlpool, input_mint);
+        };
+        Ok((resolve_whirlpool(&self.whirlpool_program, whirlpool, &data, a_to_b)?, a_to_b))
+    }
+
+    /// Reads a reserve's liquidity supply, market and fee receiver
+    pub fn lender(&self, lending_program: &Pubkey, reserve: &Pubkey) -> Result<LenderAccounts> {
+        let account = self.rpc.get_account(reserve)?;
+        if account.owner != *lending_program {
+            bail!("{} belongs to {}, not the lending program {}", reserve, account.owner, lending_program);
+        }
+        let info = ReserveInfo::parse(&account.data).map_err(|_| anyhow!("{} is not a lending reserve", reserve))?;
+
+        Ok(LenderAccounts {
+            lending_program: *lending_program,
+            reserve: *reserve,
+            reserve_liquidity_supply: info.liquidity_supply,
+            lending_market: info.lending_market,
+            lending_market_authority: pda::lending_market_authority(lending_program, &info.lending_market),
+            fee_receiver: info.fee_receiver,
+        })
+    }
+
+    /// Finds the reserve lending `mint`, picking the first one that is on
+    /// `allowed_reserves` when the lender has several markets for it
+    pub fn lender_for_mint(
+        &self,
+        lending_program: &Pubkey,
+        mint: &Pubkey,
+        allowed_reserves: &[Pubkey],
+    ) -> Result<LenderAccounts> {
+        let reserves = self.rpc.get_program_accounts_with_config(
+            lending_program,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    RESERVE_LIQUIDITY_MINT_OFFSET,
+                    mint.to_bytes().to_vec(),
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+
+        let reserve = reserves
+            .iter()
+            .map(|(address, _)| *address)
+            .find(|address| allowed_reserves.is_empty() || allowed_reserves.contains(address))
+            .ok_or_else(|| anyhow!("no allowed {} reserve lends {}", lending_program, mint))?;
+
+        self.lender(lending_program, &reserve)
+    }
+}
+
+fn resolve_whirlpool(program: &Pubkey, whirlpool: &Pubkey, data: &[u8], a_to_b: bool) -> Result<ResolvedWhirlpool> {
+    let anchor = |e: anchor_lang::error::Error| anyhow!("{}: {}", whirlpool, e);
+
+    let tokens = whirlpool_utils::parse_tokens_from_whirlpool(data).map_err(anchor)?;
+    let tick_spacing = whirlpool_utils::parse_tick_spacing_from_whirlpool(data).map_err(anchor)?;
+    let current_tick_index = whirlpool_utils::parse_tick_index_from_whirlpool(data).map_err(anchor)?;
+    let whirlpools_config = whirlpool_utils::parse_whirlpools_config(data).map_err(anchor)?;
+
+    let (oracle, oracle_bump) = pda::whirlpool_oracle(program, whirlpool);
+    let starts = whirlpool_utils::tick_array_start_indexes(current_tick_index, tick_spacing, a_to_b);
+    let tick_arrays = starts.map(|start| pda::whirlpool_tick_array(program, whirlpool, start));
+
+    Ok(ResolvedWhirlpool {
+        program: *program,
+        accounts: WhirlpoolAccounts {
+            whirlpool: *whirlpool,
+            token_mint_a: tokens.token_mint_a,
+            token_mint_b: tokens.token_mint_b,
+            token_vault_a: tokens.token_vault_a,
+            token_vault_b: tokens.token_vault_b,
+            oracle,
+            tick_arrays: tick_arrays.map(|(address, _)| address),
+        },
+        whirlpools_config,
+        fee_tier: pda::whirlpool_fee_tier(program, &whirlpools_config, tick_spacing),
+        tick_spacing,
+        bumps: WhirlpoolBumps {
+            oracle: oracle_bump,
+            tick_arrays: tick_arrays.map(|(_, bump)| bump),
+        },
+        fee_rate: u16::from_le_bytes(
+            data[WHIRLPOOL_FEE_RATE_OFFSET..WHIRLPOOL_FEE_RATE_OFFSET + 2].try_into()?,
+        ),
+        sqrt_price: u128::from_le_bytes(
+            data[WHIRLPOOL_SQRT_PRICE_OFFSET..WHIRLPOOL_SQRT_PRICE_OFFSET + 16].try_into()?,
+        ),
+    })
+}
@@ -0,0 +1,344 @@
+use anyhow::Result;
+use degen_launch::error::{translate_cpi_error, CpiProgram};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    instruction::{Instruction, InstructionError},
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::pda;
+
+// SPL token account layout: mint, owner, amount
+const TOKEN_ACCOUNT_LEN: usize = 165;
+// Token-2022 accounts with extensions carry an account type byte after the base layout
+const TOKEN_2022_ACCOUNT_TYPE: u8 = 2;
+const TOKEN_2022_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+#[derive(Clone, Copy, Debug)]
+pub struct TokenBalance {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+impl TokenBalance {
+    fn parse(data: &[u8]) -> Option<Self> {
+        let is_account = data.len() == TOKEN_ACCOUNT_LEN
+            || (data.len() > TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_LEN] == TOKEN_2022_ACCOUNT_TYPE);
+        if !is_account {
+            return None;
+        }
+        Some(Self {
+            mint: Pubkey::try_from(&data[0..32]).ok()?,
+            owner: Pubkey::try_from(&data[32..64]).ok()?,
+            amount: u64::from_le_bytes(data[64..72].try_into().ok()?),
+        })
+    }
+}
+
+/// A token account's balance before and after the simulated transaction
+#[derive(Clone, Debug)]
+pub struct BalanceChange {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+}
+
+impl BalanceChange {
+    pub fn delta(&self) -> i128 {
+        self.post as i128 - self.pre as i128
+    }
+}
+
+/// A custom program error, named from our ErrorCode where possible
+#[derive(Clone, Debug)]
+pub struct DecodedError {
+    /// Program that raised the error, which may be a CPI callee
+    pub program: Pubkey,
+    pub code: u32,
+    pub name: String,
+    pub message: String,
+    /// Our ErrorCode number (6000+) the error maps onto
+    pub error_number: Option<u32>,
+}
+
+pub struct SimulationReport {
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    pub err: Option<TransactionError>,
+    pub program_error: Option<DecodedError>,
+    pub balance_changes: Vec<BalanceChange>,
+    /// Network fee in lamports, priority fee included
+    pub fee: u64,
+    /// Fee payer's lamports before and after, which also moves with rent paid or refunded
+    pub payer: Pubkey,
+    pub payer_pre_lamports: u64,
+    pub payer_post_lamports: u64,
+}
+
+impl SimulationReport {
+    /// Net change per mint across token accounts owned by `owners`. The flash loan
+    /// fee and swap fees are already in the balances. The network fee is paid in
+    /// lamports by the fee payer, so it is in `payer_lamport_change`, not here.
+    pub fn net_change(&self, owners: &[Pubkey]) -> BTreeMap<Pubkey, i128> {
+        let mut net = BTreeMap::new();
+        for change in self.balance_changes.iter().filter(|c| owners.contains(&c.owner)) {
+            *net.entry(change.mint).or_insert(0) += change.delta();
+        }
+        net
+    }
+
+    /// Lamports the fee payer gains (negative when it pays), network fee included
+    pub fn payer_lamport_change(&self) -> i128 {
+        self.payer_post_lamports as i128 - self.payer_pre_lamports as i128
+    }
+}
+
+/// Dry-runs transactions, snapshotting every token account their instructions write
+pub struct Simulator<'a> {
+    rpc: &'a RpcClient,
+    lending_program: Pubkey,
+    whirlpool_program: Pubkey,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(rpc: &'a RpcClient, lending_program: Pubkey, whirlpool_program: Pubkey) -> Self {
+        Self { rpc, lending_program, whirlpool_program }
+    }
+
+    /// Simulates `tx`, built from `instructions`, without checking signatures.
+    /// Pre-balances are read before the simulation and it runs at or after their
+    /// slot, so transactions landing in between also show up in the diff.
+    pub fn simulate(&self, tx: &VersionedTransaction, instructions: &[Instruction]) -> Result<SimulationReport> {
+        let payer = tx.message.static_account_keys()[0];
+        let (pre, mut slot) = self.token_accounts(instructions)?;
+        let payer_pre = self.rpc.get_balance_with_commitment(&payer, CommitmentConfig::confirmed())?;
+        slot = slot.max(payer_pre.context.slot);
+
+        // The payer goes last so the token accounts line up with `pre`
+        let mut addresses: Vec<Pubkey> = pre.keys().copied().collect();
+        addresses.push(payer);
+
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(
+                tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    min_context_slot: Some(slot),
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: addresses.iter().map(Pubkey::to_string).collect(),
+                    }),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )?
+            .value;
+
+        let mut post = result.accounts.unwrap_or_default();
+        let payer_post = post.pop().flatten().map_or(0, |account| account.lamports);
+        let balance_changes = addresses
+            .iter()
+            .zip(post)
+            .map(|(address, account)| {
+                let before = pre[address];
+                let amount = account
+                    .and_then(|account| account.decode::<Account>())
+                    .and_then(|account| TokenBalance::parse(&account.data))
+                    .map_or(0, |balance| balance.amount);
+                BalanceChange {
+                    address: *address,
+                    mint: before.mint,
+                    owner: before.owner,
+                    pre: before.amount,
+                    post: amount,
+                }
+            })
+            .collect();
+
+        let logs = result.logs.unwrap_or_default();
+        let program_error = match &result.err {
+            Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+                self.decode_error(&logs, *code)
+            }
+            _ => None,
+        };
+
+        Ok(SimulationReport {
+            units_consumed: result.units_consumed,
+            program_error,
+            err: result.err,
+            logs,
+            balance_changes,
+            fee: match &tx.message {
+                VersionedMessage::Legacy(message) => self.rpc.get_fee_for_message(message)?,
+                VersionedMessage::V0(message) => self.rpc.get_fee_for_message(message)?,
+            },
+            payer,
+            payer_pre_lamports: payer_pre.value,
+            payer_post_lamports: payer_post,
+        })
+    }
+
+    /// Current balances of the SPL Token and Token-2022 accounts `instructions`
+    /// write to, and the latest slot they were read at
+    fn token_accounts(&self, instructions: &[Instruction]) -> Result<(BTreeMap<Pubkey, TokenBalance>, u64)> {
+        let writable: BTreeSet<Pubkey> = instructions
+            .iter()
+            .flat_map(|ix| ix.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        let writable: Vec<Pubkey> = writable.into_iter().collect();
+
+        let mut balances = BTreeMap::new();
+        let mut slot = 0;
+        for chunk in writable.chunks(100) {
+            let response = self
+                .rpc
+                .get_multiple_accounts_with_commitment(chunk, CommitmentConfig::confirmed())?;
+            slot = slot.max(response.context.slot);
+            for (address, account) in chunk.iter().zip(response.value) {
+                let balance = account
+                    .filter(|account| {
+                        account.owner == pda::TOKEN_PROGRAM_ID || account.owner == TOKEN_2022_PROGRAM_ID
+                    })
+                    .and_then(|account| TokenBalance::parse(&account.data));
+                if let Some(balance) = balance {
+                    balances.insert(*address, balance);
+                }
+            }
+        }
+        Ok((balances, slot))
+    }
+
+    /// Names the custom error `code`, raised by the innermost program that failed
+    fn decode_error(&self, logs: &[String], code: u32) -> Option<DecodedError> {
+        let program = logs.iter().find_map(|log| {
+            let rest = log.strip_prefix("Program ")?;
+            let (program, _) = rest.split_once(" failed: custom program error: ")?;
+            program.parse::<Pubkey>().ok()
+        })?;
+
+        let cpi_program = if program == self.whirlpool_program {
+            Some(CpiProgram::Whirlpool)
+        } else if program == self.lending_program {
+            Some(CpiProgram::Solend)
+        } else {
+            None
+        };
+
+        if let Some(cpi_program) = cpi_program {
+            let (description, mapped) = translate_cpi_error(cpi_program, code)
+                .map_or((None, None), |(description, mapped)| (Some(description), Some(mapped)));
+            return Some(DecodedError {
+                program,
+                code,
+                name: mapped.map_or_else(|| format!("{} error", cpi_program.name()), |m| m.name()),
+                message: description.map_or_else(|| format!("{} error {}", cpi_program.name(), code), str::to_string),
+                error_number: mapped.map(u32::from),
+            });
+        }
+
+        // Anchor logs "Error Code: <name>. Error Number: <n>. Error Message: <msg>."
+        let anchor_log = logs.iter().find(|log| log.contains("AnchorError"));
+        let field = |key: &str| -> Option<String> {
+            let log = anchor_log?;
+            let start = log.find(key)? + key.len();
+            Some(log[start..].split(". Error ").next()?.trim_end_matches('.').to_string())
+        };
+        Some(DecodedError {
+            program,
+            code,
+            name: field("Error Code: ").unwrap_or_else(|| "Unknown".to_string()),
+            message: field("Error Message: ").unwrap_or_default(),
+            error_number: (program == degen_launch::ID).then_some(code),
+        })
+    }
+}
+
+impl std::fmt::Display for DecodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}) from {}: {}", self.name, self.code, self.program, self.message)?;
+        if let Some(number) = self.error_number.filter(|n| *n != self.code) {
+            write!(f, " [maps to {}]", number)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(mint: Pubkey, owner: Pubkey, pre: u64, post: u64) -> BalanceChange {
+        BalanceChange {
+            address: Pubkey::new_unique(),
+            mint,
+            owner,
+            pre,
+            post,
+        }
+    }
+
+    fn report(balance_changes: Vec<BalanceChange>) -> SimulationReport {
+        SimulationReport {
+            units_consumed: Some(200_000),
+            logs: vec![],
+            err: None,
+            program_error: None,
+            balance_changes,
+            fee: 15_000,
+            payer: Pubkey::new_unique(),
+            payer_pre_lamports: 1_000_000,
+            payer_post_lamports: 985_000,
+        }
+    }
+
+    #[test]
+    fn net_change_sums_owned_accounts_per_mint() {
+        let (usdc, degen) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (operator, vault, lender) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let report = report(vec![
+            change(usdc, operator, 100, 150),
+            change(usdc, vault, 1_000, 1_200),
+            change(degen, vault, 500, 300),
+            change(usdc, lender, 10_000, 9_990),
+        ]);
+
+        let net = report.net_change(&[operator, vault]);
+        assert_eq!(net.len(), 2);
+        assert_eq!(net[&usdc], 250);
+        assert_eq!(net[&degen], -200);
+
+        assert_eq!(report.net_change(&[lender])[&usdc], -10);
+        assert!(report.net_change(&[]).is_empty());
+    }
+
+    #[test]
+    fn network_fee_is_not_a_token_change() {
+        let report = report(vec![]);
+        assert!(report.net_change(&[report.payer]).is_empty());
+        assert_eq!(report.payer_lamport_change(), -15_000);
+    }
+
+    #[test]
+    fn deltas_do_not_overflow() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let report = report(vec![change(mint, owner, u64::MAX, 0), change(mint, owner, u64::MAX, 0)]);
+        assert_eq!(report.net_change(&[owner])[&mint], -2 * u64::MAX as i128);
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{bail, Result};
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+use crate::flash_loan::FlashLoan;
+
+enum Step {
+    Instruction(Instruction),
+    FlashLoan(Box<FlashLoan>),
+}
+
+/// Collects instructions and compiles them into a v0 message, moving every
+/// account found in the lookup tables out of the static key list
+pub struct TransactionBuilder {
+    payer: Pubkey,
+    steps: Vec<Step>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+}
+
+impl TransactionBuilder {
+    pub fn new(payer: Pubkey) -> Self {
+        Self {
+            payer,
+            steps: Vec::new(),
+            lookup_tables: Vec::new(),
+            compute_unit_limit: None,
+            compute_unit_price: None,
+        }
+    }
+
+    pub fn instruction(mut self, ix: Instruction) -> Self {
+        self.steps.push(Step::Instruction(ix));
+        self
+    }
+
+    pub fn instructions(mut self, ixs: impl IntoIterator<Item = Instruction>) -> Self {
+        self.steps.extend(ixs.into_iter().map(Step::Instruction));
+        self
+    }
+
+    /// Adds a flash loan's borrow, strategy and repay, numbering the borrow by where
+    /// it lands once the compute budget instructions are in front
+    pub fn flash_loan(mut self, loan: FlashLoan) -> Self {
+        self.steps.push(Step::FlashLoan(Box::new(loan)));
+        self
+    }
+
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Priority fee in micro-lamports per compute unit
+    pub fn compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Every instruction of the transaction, in order
+    pub fn all_instructions(&self) -> Result<Vec<Instruction>> {
+        let mut ixs = Vec::with_capacity(self.steps.len() + 4);
+        if let Some(units) = self.compute_unit_limit {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(price) = self.compute_unit_price {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        for step in &self.steps {
+            match step {
+                Step::Instruction(ix) => ixs.push(ix.clone()),
+                Step::FlashLoan(loan) => {
+                    let Ok(borrow_index) = u8::try_from(ixs.len()) else {
+                        bail!("flash borrow at instruction {} is out of the lender's reach", ixs.len());
+                    };
+                    ixs.extend(loan.instructions(borrow_index));
+                }
+            }
+        }
+        Ok(ixs)
+    }
+
+    pub fn compile(&self, recent_blockhash: Hash) -> Result<VersionedMessage> {
+        let message = v0::Message::try_compile(
+            &self.payer,
+            &self.all_instructions()?,
+            &self.lookup_tables,
+            recent_blockhash,
+        )?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    pub fn sign(&self, recent_blockhash: Hash, signers: &[&dyn Signer]) -> Result<VersionedTransaction> {
+        Ok(VersionedTransaction::try_new(self.compile(recent_blockhash)?, signers)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::LenderAccounts;
+    use degen_launch::solend::{FlashRepay, FLASH_BORROW_RESERVE_LIQUIDITY_TAG};
+
+    fn loan(operator: Pubkey) -> FlashLoan {
+        FlashLoan {
+            lender: LenderAccounts {
+                lending_program: Pubkey::new_unique(),
+                reserve: Pubkey::new_unique(),
+                reserve_liquidity_supply: Pubkey::new_unique(),
+                lending_market: Pubkey::new_unique(),
+                lending_market_authority: Pubkey::new_unique(),
+                fee_receiver: Pubkey::new_unique(),
+            },
+            loan_account: Pubkey::new_unique(),
+            amount: 1_000,
+            operator,
+            strategy: Instruction::new_with_bytes(degen_launch::ID, &[7], vec![]),
+        }
+    }
+
+    #[test]
+    fn numbers_the_borrow_after_the_compute_budget() {
+        let operator = Pubkey::new_unique();
+        let loan = loan(operator);
+        let ixs = TransactionBuilder::new(operator)
+            .instruction(Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]))
+            .flash_loan(loan.clone())
+            .compute_unit_limit(400_000)
+            .compute_unit_price(1)
+            .all_instructions()
+            .unwrap();
+
+        assert_eq!(ixs.len(), 6);
+        assert_eq!(ixs[3].program_id, loan.lender.lending_program);
+        assert_eq!(ixs[3].data[0], FLASH_BORROW_RESERVE_LIQUIDITY_TAG);
+        assert_eq!(ixs[4], loan.strategy);
+        let repay = FlashRepay::parse(&ixs[5]).unwrap();
+        assert_eq!(repay.borrow_instruction_index, 3);
+        assert_eq!((repay.amount, repay.source_liquidity), (1_000, loan.loan_account));
+        assert!(ixs[5].accounts.iter().any(|meta| meta.pubkey == operator && meta.is_signer));
+    }
+}
@@ -0,0 +1,257 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use degen_launch::{FlashLoanRepaid, FlashLoanStarted, ProfitRealized, SwapExecuted, Withdrawn};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, UiInstruction, UiMessage,
+};
+use std::{fs, path::Path, str::FromStr};
+
+// Prefix Anchor puts in front of events emitted through a self-CPI
+const EVENT_IX_TAG_LE: [u8; 8] = 0x1d9a_cb51_2ea5_45e4u64.to_le_bytes();
+
+/// A decoded degen_launch event
+pub enum ProgramEvent {
+    FlashLoanStarted(FlashLoanStarted),
+    SwapExecuted(SwapExecuted),
+    FlashLoanRepaid(FlashLoanRepaid),
+    ProfitRealized(ProfitRealized),
+    Withdrawn(Withdrawn),
+}
+
+impl ProgramEvent {
+    /// Decodes a discriminator-prefixed event payload
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut payload) = data.split_at(8);
+
+        if discriminator == FlashLoanStarted::DISCRIMINATOR {
+            FlashLoanStarted::deserialize(&mut payload).ok().map(Self::FlashLoanStarted)
+        } else if discriminator == SwapExecuted::DISCRIMINATOR {
+            SwapExecuted::deserialize(&mut payload).ok().map(Self::SwapExecuted)
+        } else if discriminator == FlashLoanRepaid::DISCRIMINATOR {
+            FlashLoanRepaid::deserialize(&mut payload).ok().map(Self::FlashLoanRepaid)
+        } else if discriminator == ProfitRealized::DISCRIMINATOR {
+            ProfitRealized::deserialize(&mut payload).ok().map(Self::ProfitRealized)
+        } else if discriminator == Withdrawn::DISCRIMINATOR {
+            Withdrawn::deserialize(&mut payload).ok().map(Self::Withdrawn)
+        } else {
+            None
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::FlashLoanStarted(_) => "FlashLoanStarted",
+            Self::SwapExecuted(_) => "SwapExecuted",
+            Self::FlashLoanRepaid(_) => "FlashLoanRepaid",
+            Self::ProfitRealized(_) => "ProfitRealized",
+            Self::Withdrawn(_) => "Withdrawn",
+        }
+    }
+
+    /// Event fields as JSON, pubkeys in base58
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::FlashLoanStarted(e) => json!({
+                "lending_program": e.lending_program.to_string(),
+                "reserve": e.reserve.to_string(),
+                "destination": e.destination.to_string(),
+                "amount": e.amount,
+                "slot": e.slot,
+            }),
+            Self::SwapExecuted(e) => json!({
+                "pool": e.pool.to_string(),
+                "input_mint": e.input_mint.to_string(),
+                "output_mint": e.output_mint.to_string(),
+                "amount_in": e.amount_in,
+                "amount_out": e.amount_out,
+                "slot": e.slot,
+            }),
+            Self::FlashLoanRepaid(e) => json!({
+                "reserve_liquidity_supply": e.reserve_liquidity_supply.to_string(),
+                "amount": e.amount,
+                "fee": e.fee,
+                "slot": e.slot,
+            }),
+            Self::ProfitRealized(e) => json!({
+                "operator": e.operator.to_string(),
+                "mint": e.mint.to_string(),
+                "profit": e.profit,
+                "distributed": e.distributed,
+                "slot": e.slot,
+            }),
+            Self::Withdrawn(e) => json!({
+                "treasurer": e.treasurer.to_string(),
+                "mint": e.mint.to_string(),
+                "amount": e.amount,
+                "slot": e.slot,
+            }),
+        }
+    }
+}
+
+/// Where an event was found in the transaction
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Source {
+    /// `Program data:` log line written by `emit!`
+    Log,
+    /// Self-CPI inner instruction written by `emit_cpi!`
+    Cpi,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Log => "log",
+            Self::Cpi => "cpi",
+        }
+    }
+}
+
+pub struct IndexedEvent {
+    pub signature: String,
+    pub slot: u64,
+    /// Position of the event within its transaction
+    pub index: u32,
+    pub source: Source,
+    pub event: ProgramEvent,
+}
+
+/// Reads a transaction recorded as the `result` of a `getTransaction` call
+/// with `json` encoding, e.g. a test fixture
+pub fn load_transaction(path: &Path) -> anyhow::Result<EncodedConfirmedTransactionWithStatusMeta> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Decodes every event `program_id` emitted in `tx`, from logs and from CPI events.
+/// Works on RPC responses and on recorded transaction JSON alike.
+pub fn decode_transaction(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    program_id: &Pubkey,
+) -> Vec<IndexedEvent> {
+    let signature = transaction_signature(&tx.transaction.transaction).unwrap_or_default();
+    let mut events = Vec::new();
+
+    let Some(meta) = tx.transaction.meta.as_ref() else {
+        return events;
+    };
+
+    let mut push = |source: Source, event: ProgramEvent| {
+        events.push(IndexedEvent {
+            signature: signature.clone(),
+            slot: tx.slot,
+            index: 0,
+            source,
+            event,
+        });
+    };
+
+    if let OptionSerializer::Some(logs) = &meta.log_messages {
+        for data in program_data_logs(logs, program_id) {
+            if let Some(event) = ProgramEvent::decode(&data) {
+                push(Source::Log, event);
+            }
+        }
+    }
+
+    if let OptionSerializer::Some(inner) = &meta.inner_instructions {
+        let account_keys = account_keys(tx);
+        for ix in inner.iter().flat_map(|set| set.instructions.iter()) {
+            let UiInstruction::Compiled(ix) = ix else {
+                continue;
+            };
+            if account_keys.get(ix.program_id_index as usize) != Some(program_id) {
+                continue;
+            }
+            let Ok(data) = bs58::decode(&ix.data).into_vec() else {
+                continue;
+            };
+            if let Some(payload) = data.strip_prefix(&EVENT_IX_TAG_LE[..]) {
+                if let Some(event) = ProgramEvent::decode(payload) {
+                    push(Source::Cpi, event);
+                }
+            }
+        }
+    }
+
+    for (index, event) in events.iter_mut().enumerate() {
+        event.index = index as u32;
+    }
+    events
+}
+
+/// Payloads of `Program data:` lines logged while `program_id` was the executing program
+fn program_data_logs(logs: &[String], program_id: &Pubkey) -> Vec<Vec<u8>> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut payloads = Vec::new();
+
+    for log in logs {
+        let Some(rest) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() == Some(&program_id.as_str()) {
+                if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) {
+                    payloads.push(bytes);
+                }
+            }
+        } else if let Some((id, status)) = rest.split_once(' ') {
+            if status.starts_with("invoke [") {
+                stack.push(id);
+            } else if status == "success" || status.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+
+    payloads
+}
+
+/// Static keys followed by keys loaded from lookup tables, in instruction index order
+fn account_keys(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Pubkey> {
+    let mut keys: Vec<Pubkey> = match &tx.transaction.transaction {
+        EncodedTransaction::Json(ui) => match &ui.message {
+            UiMessage::Raw(raw) => raw
+                .account_keys
+                .iter()
+                .filter_map(|key| Pubkey::from_str(key).ok())
+                .collect(),
+            UiMessage::Parsed(parsed) => parsed
+                .account_keys
+                .iter()
+                .filter_map(|key| Pubkey::from_str(&key.pubkey).ok())
+                .collect(),
+        },
+        encoded => encoded
+            .decode()
+            .map(|versioned| versioned.message.static_account_keys().to_vec())
+            .unwrap_or_default(),
+    };
+
+    if let Some(OptionSerializer::Some(loaded)) =
+        tx.transaction.meta.as_ref().map(|meta| &meta.loaded_addresses)
+    {
+        for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            if let Ok(key) = Pubkey::from_str(key) {
+                keys.push(key);
+            }
+        }
+    }
+
+    keys
+}
+
+fn transaction_signature(tx: &EncodedTransaction) -> Option<String> {
+    match tx {
+        EncodedTransaction::Json(ui) => ui.signatures.first().cloned(),
+        encoded => encoded
+            .decode()
+            .and_then(|versioned| versioned.signatures.first().map(|sig| sig.to_string())),
+    }
+}
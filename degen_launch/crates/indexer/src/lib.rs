@@ -0,0 +1,5 @@
+pub mod decode;
+pub mod store;
+
+pub use decode::{decode_transaction, load_transaction, IndexedEvent, ProgramEvent, Source};
+pub use store::EventStore;
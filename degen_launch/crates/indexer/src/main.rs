@@ -0,0 +1,89 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use degen_launch_indexer::{decode_transaction, load_transaction, EventStore};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{path::PathBuf, str::FromStr};
+
+#[derive(Parser)]
+#[command(name = "degen-indexer", about = "Index degen_launch events into SQLite")]
+struct Cli {
+    /// SQLite database file
+    #[arg(long, default_value = "events.db")]
+    db: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index recorded getTransaction JSON files
+    Ingest { files: Vec<PathBuf> },
+    /// Index the program's most recent transactions from an RPC node
+    Sync {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        rpc_url: String,
+        /// How many recent signatures to look at
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Print indexed events from a slot onwards
+    List {
+        #[arg(long, default_value_t = 0)]
+        since_slot: u64,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = EventStore::open(&cli.db)?;
+
+    match cli.command {
+        Command::Ingest { files } => {
+            for file in files {
+                let tx = load_transaction(&file)?;
+                let inserted = store.insert(&decode_transaction(&tx, &degen_launch::ID))?;
+                println!("{}: {} new events", file.display(), inserted);
+            }
+        }
+        Command::Sync { rpc_url, limit } => {
+            let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+            let signatures = client.get_signatures_for_address_with_config(
+                &degen_launch::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    limit: Some(limit),
+                    ..Default::default()
+                },
+            )?;
+
+            let mut inserted = 0;
+            // Oldest first so an interrupted sync leaves no gaps behind the latest slot
+            for status in signatures.iter().rev().filter(|s| s.err.is_none()) {
+                if store.contains_signature(&status.signature)? {
+                    continue;
+                }
+                let tx = client.get_transaction_with_config(
+                    &Signature::from_str(&status.signature)?,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Json),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )?;
+                inserted += store.insert(&decode_transaction(&tx, &degen_launch::ID))?;
+            }
+            println!("{} new events, latest slot {:?}", inserted, store.latest_slot()?);
+        }
+        Command::List { since_slot } => {
+            for e in store.events_since(since_slot)? {
+                println!("{} {} #{} {} ({}) {}", e.slot, e.signature, e.index, e.kind, e.source, e.payload);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+use crate::decode::IndexedEvent;
+
+/// A decoded event as stored in the database
+pub struct StoredEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub index: u32,
+    pub kind: String,
+    pub source: String,
+    pub payload: serde_json::Value,
+}
+
+/// Local SQLite store of decoded events, keyed by transaction signature and slot
+pub struct EventStore {
+    conn: Connection,
+}
+
+impl EventStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                signature   TEXT    NOT NULL,
+                slot        INTEGER NOT NULL,
+                event_index INTEGER NOT NULL,
+                kind        TEXT    NOT NULL,
+                source      TEXT    NOT NULL,
+                payload     TEXT    NOT NULL,
+                PRIMARY KEY (signature, event_index)
+            );
+            CREATE INDEX IF NOT EXISTS events_slot ON events (slot);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Stores `events`, skipping any already indexed. Returns how many were new.
+    pub fn insert(&mut self, events: &[IndexedEvent]) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO events (signature, slot, event_index, kind, source, payload)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for e in events {
+                inserted += stmt.execute(params![
+                    e.signature,
+                    e.slot as i64,
+                    e.index,
+                    e.event.kind(),
+                    e.source.as_str(),
+                    e.event.to_json().to_string(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    pub fn contains_signature(&self, signature: &str) -> rusqlite::Result<bool> {
+        self.conn
+            .query_row("SELECT 1 FROM events WHERE signature = ?1 LIMIT 1", [signature], |_| Ok(()))
+            .optional()
+            .map(|row| row.is_some())
+    }
+
+    /// Highest slot indexed so far
+    pub fn latest_slot(&self) -> rusqlite::Result<Option<u64>> {
+        self.conn
+            .query_row("SELECT MAX(slot) FROM events", [], |row| row.get::<_, Option<i64>>(0))
+            .map(|slot| slot.map(|slot| slot as u64))
+    }
+
+    /// Events at or after `slot`, oldest first
+    pub fn events_since(&self, slot: u64) -> rusqlite::Result<Vec<StoredEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT signature, slot, event_index, kind, source, payload FROM events
+             WHERE slot >= ?1 ORDER BY slot, signature, event_index",
+        )?;
+        let rows = stmt.query_map([slot as i64], |row| {
+            let payload: String = row.get(5)?;
+            Ok(StoredEvent {
+                signature: row.get(0)?,
+                slot: row.get::<_, i64>(1)? as u64,
+                index: row.get(2)?,
+                kind: row.get(3)?,
+                source: row.get(4)?,
+                payload: serde_json::from_str(&payload).unwrap_or_default(),
+            })
+        })?;
+        rows.collect()
+    }
+}
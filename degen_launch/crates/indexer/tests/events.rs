@@ -0,0 +1,104 @@
+use degen_launch_indexer::{decode_transaction, load_transaction, EventStore, IndexedEvent, ProgramEvent, Source};
+use solana_sdk::pubkey::Pubkey;
+use std::{path::Path, str::FromStr};
+
+fn fixture(name: &str) -> Vec<IndexedEvent> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    let tx = load_transaction(&path).unwrap();
+    decode_transaction(&tx, &degen_launch::ID)
+}
+
+fn pubkey(key: &str) -> Pubkey {
+    Pubkey::from_str(key).unwrap()
+}
+
+#[test]
+fn decodes_logged_events_of_the_program_only() {
+    let events = fixture("selfdump_log_events.json");
+
+    let kinds: Vec<_> = events.iter().map(|e| e.event.kind()).collect();
+    assert_eq!(kinds, ["FlashLoanStarted", "SwapExecuted", "FlashLoanRepaid", "ProfitRealized"]);
+    assert!(events.iter().all(|e| e.source == Source::Log && e.slot == 318_204_117));
+    assert_eq!(events.iter().map(|e| e.index).collect::<Vec<_>>(), [0, 1, 2, 3]);
+
+    let ProgramEvent::FlashLoanStarted(started) = &events[0].event else {
+        panic!("expected FlashLoanStarted");
+    };
+    assert_eq!(started.reserve, pubkey("Borqy3dEjw9az7Uj9nW69A9ZDansFGHWEggUx7tkv44f"));
+    assert_eq!(started.destination, pubkey("B1rADWGjAKbZYVHMYhS5ZKyMbqFB65vmNNxUXdgFBoh3"));
+    assert_eq!(started.amount, 250_000_000);
+
+    let ProgramEvent::SwapExecuted(swap) = &events[1].event else {
+        panic!("expected SwapExecuted");
+    };
+    assert_eq!(swap.pool, pubkey("7Atmc8eC2CovjDTvsNYoAinXNfSHQjzVp3bJs9PksFtN"));
+    assert_eq!((swap.amount_in, swap.amount_out), (250_000_000, 251_380_000));
+
+    let ProgramEvent::FlashLoanRepaid(repaid) = &events[2].event else {
+        panic!("expected FlashLoanRepaid");
+    };
+    assert_eq!(repaid.reserve_liquidity_supply, pubkey("AUH6c4QLMr2qQr9N5Kkpz5astDM9gBNroXCSxQiFTGQv"));
+    assert_eq!((repaid.amount, repaid.fee), (250_000_000, 225_000));
+
+    let ProgramEvent::ProfitRealized(realized) = &events[3].event else {
+        panic!("expected ProfitRealized");
+    };
+    assert_eq!(realized.operator, pubkey("6anbDQNCcVh2f6okexjaX1VGj6tEnizJ1kV5UTBS8Zhi"));
+    assert_eq!((realized.profit, realized.distributed), (251_155_000, 0));
+}
+
+#[test]
+fn decodes_cpi_events() {
+    let events = fixture("withdraw_cpi_event.json");
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].source, Source::Cpi);
+    assert_eq!(events[0].slot, 318_210_944);
+    let ProgramEvent::Withdrawn(withdrawn) = &events[0].event else {
+        panic!("expected Withdrawn");
+    };
+    assert_eq!(withdrawn.treasurer, pubkey("7wE4VikYAdTxrVDHj1xsZTb8UVXhkaf8vZvb4Uvh9zz"));
+    assert_eq!(withdrawn.mint, pubkey("6Mpz5xm4qTBEoYyKNyQScWmcqTuNeimHewvQRjqBfBmy"));
+    assert_eq!(withdrawn.amount, 1_500_000_000);
+    assert_eq!(withdrawn.slot, 318_210_944);
+}
+
+#[test]
+fn ignores_other_programs() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/selfdump_log_events.json");
+    let tx = load_transaction(&path).unwrap();
+    assert!(decode_transaction(&tx, &Pubkey::new_unique()).is_empty());
+}
+
+#[test]
+fn stores_events_once() {
+    let logged = fixture("selfdump_log_events.json");
+    let withdrawn = fixture("withdraw_cpi_event.json");
+    let mut store = EventStore::in_memory().unwrap();
+
+    assert_eq!(store.latest_slot().unwrap(), None);
+    assert_eq!(store.insert(&logged).unwrap(), 4);
+    assert_eq!(store.insert(&withdrawn).unwrap(), 1);
+    assert_eq!(store.insert(&logged).unwrap(), 0);
+
+    assert!(store.contains_signature(&logged[0].signature).unwrap());
+    assert!(!store.contains_signature("1111111111111111111111111111111111111111111111111111111111111111").unwrap());
+    assert_eq!(store.latest_slot().unwrap(), Some(318_210_944));
+
+    let stored = store.events_since(0).unwrap();
+    assert_eq!(stored.len(), 5);
+    for (stored, event) in stored.iter().zip(logged.iter().chain(withdrawn.iter())) {
+        assert_eq!(stored.signature, event.signature);
+        assert_eq!(stored.slot, event.slot);
+        assert_eq!(stored.index, event.index);
+        assert_eq!(stored.kind, event.event.kind());
+        assert_eq!(stored.source, event.source.as_str());
+        assert_eq!(stored.payload, event.event.to_json());
+    }
+    assert_eq!(stored[4].payload["amount"], 1_500_000_000u64);
+
+    let recent = store.events_since(318_210_944).unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].kind, "Withdrawn");
+    assert_eq!(recent[0].source, "cpi");
+}
@@ -0,0 +1,573 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::{self, Approve, Token, TokenAccount};
+
+use crate::config::{Config, CONFIG_SEED};
+use crate::guard::FlashLoan;
+use crate::profit;
+use crate::receipt::{ExecutionReceipt, EXECUTION_RECEIPT_SEED};
+use crate::stats::{Strategy, StrategyStats, STRATEGY_STATS_SEED};
+use crate::vault::{self, VAULT_AUTHORITY_SEED};
+use crate::error::ErrorCode;
+use crate::whirlpool_utils::{self, WhirlpoolBumps};
+
+/// Accounts of one Orca whirlpool used as a strategy leg
+#[derive(Accounts)]
+pub struct OrcaPool<'info> {
+    /// CHECK: Owner checked here, vaults checked against its data before swapping
+    #[account(mut, owner = whirlpool_cpi::ID)]
+    pub whirlpool: AccountInfo<'info>,
+    /// CHECK: Must match whirlpool.token_vault_a
+    #[account(mut)]
+    pub token_vault_a: AccountInfo<'info>,
+    /// CHECK: Must match whirlpool.token_vault_b
+    #[account(mut)]
+    pub token_vault_b: AccountInfo<'info>,
+    /// CHECK: Verified in CPI
+    #[account(mut, owner = whirlpool_cpi::ID)]
+    pub tick_array_0: AccountInfo<'info>,
+    /// CHECK: Verified in CPI
+    #[account(mut, owner = whirlpool_cpi::ID)]
+    pub tick_array_1: AccountInfo<'info>,
+    /// CHECK: Verified in CPI
+    #[account(mut, owner = whirlpool_cpi::ID)]
+    pub tick_array_2: AccountInfo<'info>,
+    /// CHECK: Verified in CPI
+    pub oracle: AccountInfo<'info>,
+}
+
+impl<'info> OrcaPool<'info> {
+    /// Checks the pool trades `input_mint` for `output_mint` through the vaults passed
+    /// in, and the oracle and tick arrays against the ones the swap walks using the
+    /// client's `bumps`. Reads the whirlpool once and returns the swap direction, true
+    /// when that is A to B
+    pub fn check_leg(
+        &self,
+        whirlpool_program: &Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        bumps: &WhirlpoolBumps,
+    ) -> Result<bool> {
+        let data = self.whirlpool.try_borrow_data()?;
+        let tokens = whirlpool_utils::parse_tokens_from_whirlpool(&data)?;
+        require_keys_eq!(self.token_vault_a.key(), tokens.token_vault_a, ErrorCode::VenueAccountMismatch);
+        require_keys_eq!(self.token_vault_b.key(), tokens.token_vault_b, ErrorCode::VenueAccountMismatch);
+
+        let a_to_b = if input_mint == tokens.token_mint_a && output_mint == tokens.token_mint_b {
+            true
+        } else if input_mint == tokens.token_mint_b && output_mint == tokens.token_mint_a {
+            false
+        } else {
+            return Err(ErrorCode::VenueAccountMismatch.into());
+        };
+
+        whirlpool_utils::verify_swap_pdas(
+            whirlpool_program,
+            &self.whirlpool.key(),
+            &data,
+            a_to_b,
+            bumps,
+            &self.oracle.key(),
+            [
+                Some(self.tick_array_0.key()),
+                Some(self.tick_array_1.key()),
+                Some(self.tick_array_2.key()),
+            ],
+        )?;
+        Ok(a_to_b)
+    }
+
+    /// Swaps all of `amount_in` from `input` into `output` in the direction
+    /// `check_leg` returned for their mints. Returns the amount that landed in `output`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        &self,
+        whirlpool_program: &AccountInfo<'info>,
+        token_authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        input: &mut Account<'info, TokenAccount>,
+        output: &mut Account<'info, TokenAccount>,
+        a_to_b: bool,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<u64> {
+        let (owner_a, owner_b) = if a_to_b {
+            (input.to_account_info(), output.to_account_info())
+        } else {
+            (output.to_account_info(), input.to_account_info())
+        };
+
+        let cpi_accounts = whirlpool_cpi::cpi::accounts::Swap {
+            whirlpool: self.whirlpool.to_account_info(),
+            token_program: token_program.clone(),
+            token_authority: token_authority.clone(),
+            token_owner_account_a: owner_a,
+            token_vault_a: self.token_vault_a.to_account_info(),
+            token_owner_account_b: owner_b,
+            token_vault_b: self.token_vault_b.to_account_info(),
+            tick_array_0: self.tick_array_0.to_account_info(),
+            tick_array_1: self.tick_array_1.to_account_info(),
+            tick_array_2: self.tick_array_2.to_account_info(),
+            oracle: self.oracle.to_account_info(),
+        };
+
+        let balance_before = output.amount;
+
+        whirlpool_cpi::cpi::swap(
+            CpiContext::new_with_signer(whirlpool_program.clone(), cpi_accounts, signer_seeds),
+            amount_in,
+            minimum_amount_out,
+            whirlpool_utils::sqrt_price_limit(a_to_b),
+            true, // amount_specified_is_input
+            a_to_b,
+        )?;
+
+        input.reload()?;
+        output.reload()?;
+
+        let amount_out = output.amount.saturating_sub(balance_before);
+        require_gte!(amount_out, minimum_amount_out, ErrorCode::SlippageExceeded);
+
+        Ok(amount_out)
+    }
+}
+
+/// Sets `amount + flash_loan_fee` of `proceeds` aside for the lender's flash repay by
+/// approving `operator`, who signs the repay, to move that much out of `from`.
+/// Returns what is left, failing unless that is at least `min_profit`
+#[allow(clippy::too_many_arguments)]
+pub fn approve_repayment<'info>(
+    token_program: &AccountInfo<'info>,
+    token_authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    from: &Account<'info, TokenAccount>,
+    operator: &AccountInfo<'info>,
+    amount: u64,
+    flash_loan_fee: u64,
+    proceeds: u64,
+    min_profit: u64,
+) -> Result<u64> {
+    let repay_amount = amount
+        .checked_add(flash_loan_fee)
+        .ok_or(ErrorCode::InsufficientProfit)?;
+    let profit = proceeds
+        .checked_sub(repay_amount)
+        .ok_or(ErrorCode::InsufficientProfit)?;
+    require_gte!(profit, min_profit, ErrorCode::InsufficientProfit);
+
+    token::approve(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Approve {
+                to: from.to_account_info(),
+                delegate: operator.clone(),
+                authority: token_authority.clone(),
+            },
+            signer_seeds,
+        ),
+        repay_amount,
+    )?;
+
+    Ok(profit)
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(
+    amount: u64,
+    min_degen_out: u64,
+    min_usdc_out: u64,
+    min_profit: u64,
+    receipt_nonce: u64,
+)]
+pub struct ExecuteRoundTripArbitrage<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, constraint = !config.paused @ ErrorCode::Paused)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator running the strategy, pays for the receipt if one is written
+    #[account(mut, constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
+    /// CHECK: Program vault authority PDA, signs swaps and the repayment approval
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Address checked against the Whirlpool program ID
+    #[account(address = whirlpool_cpi::ID)]
+    pub whirlpool_program: AccountInfo<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Vault holding the borrowed USDC, receives the proceeds of pool B
+    #[account(mut, token::authority = vault_authority)]
+    pub usdc_account: Account<'info, TokenAccount>,
+
+    /// Vault receiving DEGEN from pool A and spending it on pool B
+    #[account(mut, token::authority = vault_authority)]
+    pub degen_account: Account<'info, TokenAccount>,
+
+    /// Reserve the loan was drawn from
+    /// CHECK: Owned by the lender; allowlisted and read for its liquidity supply and fee
+    #[account(owner = config.lending_program @ ErrorCode::ReserveNotAllowed)]
+    pub reserve: AccountInfo<'info>,
+
+    /// Reserve liquidity supply the loan is repaid to, checked against the reserve
+    #[account(token::mint = usdc_account.mint)]
+    pub reserve_liquidity_supply: Account<'info, TokenAccount>,
+
+    /// CHECK: Instructions sysvar, read for the lender's borrow and repay around this one
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Pool DEGEN is bought on
+    pub pool_a: OrcaPool<'info>,
+
+    /// Pool DEGEN is sold on
+    pub pool_b: OrcaPool<'info>,
+
+    /// Statistics of this strategy on pool A
+    #[account(
+        mut,
+        seeds = [STRATEGY_STATS_SEED, Strategy::RoundTrip.seed(), pool_a.whirlpool.key().as_ref()],
+        bump = stats.bump,
+    )]
+    pub stats: Box<Account<'info, StrategyStats>>,
+
+    /// Optional durable record of this run
+    #[account(
+        init,
+        payer = operator,
+        space = 8 + ExecutionReceipt::INIT_SPACE,
+        seeds = [EXECUTION_RECEIPT_SEED, operator.key().as_ref(), &receipt_nonce.to_le_bytes()],
+        bump,
+    )]
+    pub receipt: Option<Box<Account<'info, ExecutionReceipt>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Amounts produced by one round trip
+pub struct RoundTripOutcome {
+    /// Fee the lender charges on the loan, read from the reserve
+    pub flash_loan_fee: u64,
+    pub degen_bought: u64,
+    pub usdc_received: u64,
+    pub profit: u64,
+    /// Part of the profit paid out to the operator and beneficiaries
+    pub distributed: u64,
+}
+
+impl<'info> ExecuteRoundTripArbitrage<'info> {
+    /// Buys DEGEN on pool A with the borrowed `amount`, sells it on pool B,
+    /// sets `amount` plus the reserve's flash loan fee aside for the repay and
+    /// requires at least `min_profit` left over. The profit is then split as configured, paying
+    /// into `profit_accounts`. `bumps` are each pool's oracle and tick array bumps.
+    pub fn execute(
+        &mut self,
+        amount: u64,
+        min_degen_out: u64,
+        min_usdc_out: u64,
+        min_profit: u64,
+        bumps: &[WhirlpoolBumps; 2],
+        profit_accounts: &[AccountInfo<'info>],
+    ) -> Result<RoundTripOutcome> {
+        let flash_loan_fee = FlashLoan::load(
+            &self.config,
+            &self.instructions,
+            &self.reserve,
+            &self.reserve_liquidity_supply.key(),
+            &self.usdc_account.key(),
+            amount,
+        )?
+        .fee;
+        self.config.require_borrow_amount(amount)?;
+        let min_profit = self.config.min_profit(min_profit);
+
+        let whirlpool_program = self.whirlpool_program.to_account_info();
+        let legs = [
+            (&self.pool_a, self.usdc_account.mint, self.degen_account.mint),
+            (&self.pool_b, self.degen_account.mint, self.usdc_account.mint),
+        ];
+        let mut a_to_b = [false; 2];
+        for (((pool, input_mint, output_mint), bumps), a_to_b) in legs.into_iter().zip(bumps).zip(&mut a_to_b) {
+            self.config.require_pool(&pool.whirlpool.key())?;
+            *a_to_b = pool.check_leg(&whirlpool_program.key(), input_mint, output_mint, bumps)?;
+        }
+
+        let token_authority = self.vault_authority.to_account_info();
+        let token_program = self.token_program.to_account_info();
+        let bump = [self.config.vault_authority_bump];
+        let seeds = vault::vault_signer_seeds(&bump);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        let degen_before = self.degen_account.amount;
+        let degen_bought = self.pool_a.swap(
+            &whirlpool_program,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            &mut self.usdc_account,
+            &mut self.degen_account,
+            a_to_b[0],
+            amount,
+            min_degen_out,
+        )?;
+
+        let usdc_received = self.pool_b.swap(
+            &whirlpool_program,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            &mut self.degen_account,
+            &mut self.usdc_account,
+            a_to_b[1],
+            degen_bought,
+            min_usdc_out,
+        )?;
+
+        // The round trip must not eat into DEGEN the account already held
+        require_gte!(self.degen_account.amount, degen_before, ErrorCode::InvalidRoute);
+
+        let profit = approve_repayment(
+            &token_program,
+            &token_authority,
+            signer_seeds,
+            &self.usdc_account,
+            &self.operator.to_account_info(),
+            amount,
+            flash_loan_fee,
+            usdc_received,
+            min_profit,
+        )?;
+
+        let distributed = profit::distribute_profit(
+            &self.config,
+            &self.operator.key(),
+            profit,
+            &self.usdc_account,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            profit_accounts,
+        )?;
+
+        self.stats.record_success(amount, flash_loan_fee, profit)?;
+
+        Ok(RoundTripOutcome {
+            flash_loan_fee,
+            degen_bought,
+            usdc_received,
+            profit,
+            distributed,
+        })
+    }
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(amount: u64, min_profit: u64, receipt_nonce: u64)]
+pub struct ExecuteTriangularArbitrage<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, constraint = !config.paused @ ErrorCode::Paused)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator running the strategy, pays for the receipt if one is written
+    #[account(mut, constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
+    /// CHECK: Program vault authority PDA, signs swaps and the repayment approval
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Address checked against the Whirlpool program ID
+    #[account(address = whirlpool_cpi::ID)]
+    pub whirlpool_program: AccountInfo<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Vault holding the borrowed USDC, spent on pool 1 and refilled by pool 3
+    #[account(mut, token::authority = vault_authority)]
+    pub usdc_account: Account<'info, TokenAccount>,
+
+    /// Vault for the first intermediate token (e.g. SOL), bought on pool 1 and sold on pool 2
+    #[account(mut, token::authority = vault_authority)]
+    pub hop_1_account: Account<'info, TokenAccount>,
+
+    /// Vault for the second intermediate token (e.g. DEGEN), bought on pool 2 and sold on pool 3
+    #[account(mut, token::authority = vault_authority)]
+    pub hop_2_account: Account<'info, TokenAccount>,
+
+    /// Reserve the loan was drawn from
+    /// CHECK: Owned by the lender; allowlisted and read for its liquidity supply and fee
+    #[account(owner = config.lending_program @ ErrorCode::ReserveNotAllowed)]
+    pub reserve: AccountInfo<'info>,
+
+    /// Reserve liquidity supply the loan is repaid to, checked against the reserve
+    #[account(token::mint = usdc_account.mint)]
+    pub reserve_liquidity_supply: Account<'info, TokenAccount>,
+
+    /// CHECK: Instructions sysvar, read for the lender's borrow and repay around this one
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// USDC -> hop 1
+    pub pool_1: OrcaPool<'info>,
+
+    /// hop 1 -> hop 2
+    pub pool_2: OrcaPool<'info>,
+
+    /// hop 2 -> USDC
+    pub pool_3: OrcaPool<'info>,
+
+    /// Statistics of this strategy on pool 1
+    #[account(
+        mut,
+        seeds = [STRATEGY_STATS_SEED, Strategy::Triangular.seed(), pool_1.whirlpool.key().as_ref()],
+        bump = stats.bump,
+    )]
+    pub stats: Box<Account<'info, StrategyStats>>,
+
+    /// Optional durable record of this run
+    #[account(
+        init,
+        payer = operator,
+        space = 8 + ExecutionReceipt::INIT_SPACE,
+        seeds = [EXECUTION_RECEIPT_SEED, operator.key().as_ref(), &receipt_nonce.to_le_bytes()],
+        bump,
+    )]
+    pub receipt: Option<Box<Account<'info, ExecutionReceipt>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Amounts produced by one triangular cycle
+pub struct TriangularOutcome {
+    /// Fee the lender charges on the loan, read from the reserve
+    pub flash_loan_fee: u64,
+    pub hop_1_received: u64,
+    pub hop_2_received: u64,
+    pub usdc_received: u64,
+    pub profit: u64,
+    /// Part of the profit paid out to the operator and beneficiaries
+    pub distributed: u64,
+}
+
+impl<'info> ExecuteTriangularArbitrage<'info> {
+    /// Runs USDC -> hop 1 -> hop 2 -> USDC with the borrowed `amount`, sets `amount`
+    /// plus the reserve's flash loan fee aside for the repay and requires at least
+    /// `min_profit` left over. Legs carry no individual min-out; the final profit check covers
+    /// the cycle. The profit is then split as configured, paying into `profit_accounts`.
+    /// `bumps` are each pool's oracle and tick array bumps.
+    pub fn execute(
+        &mut self,
+        amount: u64,
+        min_profit: u64,
+        bumps: &[WhirlpoolBumps; 3],
+        profit_accounts: &[AccountInfo<'info>],
+    ) -> Result<TriangularOutcome> {
+        let flash_loan_fee = FlashLoan::load(
+            &self.config,
+            &self.instructions,
+            &self.reserve,
+            &self.reserve_liquidity_supply.key(),
+            &self.usdc_account.key(),
+            amount,
+        )?
+        .fee;
+        self.config.require_borrow_amount(amount)?;
+        let min_profit = self.config.min_profit(min_profit);
+
+        let whirlpool_program = self.whirlpool_program.to_account_info();
+        let token_authority = self.vault_authority.to_account_info();
+        let token_program = self.token_program.to_account_info();
+        let bump = [self.config.vault_authority_bump];
+        let seeds = vault::vault_signer_seeds(&bump);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        let legs = [
+            (&self.pool_1, self.usdc_account.mint, self.hop_1_account.mint),
+            (&self.pool_2, self.hop_1_account.mint, self.hop_2_account.mint),
+            (&self.pool_3, self.hop_2_account.mint, self.usdc_account.mint),
+        ];
+        let mut a_to_b = [false; 3];
+        for (((pool, input_mint, output_mint), bumps), a_to_b) in legs.into_iter().zip(bumps).zip(&mut a_to_b) {
+            self.config.require_pool(&pool.whirlpool.key())?;
+            *a_to_b = pool.check_leg(&whirlpool_program.key(), input_mint, output_mint, bumps)?;
+        }
+
+        let hop_1_before = self.hop_1_account.amount;
+        let hop_2_before = self.hop_2_account.amount;
+
+        let hop_1_received = self.pool_1.swap(
+            &whirlpool_program,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            &mut self.usdc_account,
+            &mut self.hop_1_account,
+            a_to_b[0],
+            amount,
+            0,
+        )?;
+
+        let hop_2_received = self.pool_2.swap(
+            &whirlpool_program,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            &mut self.hop_1_account,
+            &mut self.hop_2_account,
+            a_to_b[1],
+            hop_1_received,
+            0,
+        )?;
+
+        let usdc_received = self.pool_3.swap(
+            &whirlpool_program,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            &mut self.hop_2_account,
+            &mut self.usdc_account,
+            a_to_b[2],
+            hop_2_received,
+            0,
+        )?;
+
+        // Intermediate balances must end where they started
+        require_gte!(self.hop_1_account.amount, hop_1_before, ErrorCode::InvalidRoute);
+        require_gte!(self.hop_2_account.amount, hop_2_before, ErrorCode::InvalidRoute);
+
+        let profit = approve_repayment(
+            &token_program,
+            &token_authority,
+            signer_seeds,
+            &self.usdc_account,
+            &self.operator.to_account_info(),
+            amount,
+            flash_loan_fee,
+            usdc_received,
+            min_profit,
+        )?;
+
+        let distributed = profit::distribute_profit(
+            &self.config,
+            &self.operator.key(),
+            profit,
+            &self.usdc_account,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            profit_accounts,
+        )?;
+
+        self.stats.record_success(amount, flash_loan_fee, profit)?;
+
+        Ok(TriangularOutcome {
+            flash_loan_fee,
+            hop_1_received,
+            hop_2_received,
+            usdc_received,
+            profit,
+            distributed,
+        })
+    }
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+
+use crate::vault::VAULT_AUTHORITY_SEED;
+use crate::error::ErrorCode;
+
+pub const CONFIG_SEED: &[u8] = b"config";
+
+pub const MAX_ALLOWED_RESERVES: usize = 8;
+pub const MAX_ALLOWED_POOLS: usize = 32;
+pub const MAX_OPERATORS: usize = 16;
+pub const MAX_TREASURERS: usize = 4;
+pub const MAX_BENEFICIARIES: usize = 4;
+
+/// Basis point denominator for profit shares
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// A fixed recipient of part of every realised profit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct Beneficiary {
+    /// Owner of the token accounts the share is paid into
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+/// Roles the admin can grant. The admin role itself moves via update_config.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// May call strategy instructions
+    Operator,
+    /// May move funds out of the program
+    Treasurer,
+}
+
+/// Program-wide settings, stored in a single PDA at [CONFIG_SEED]
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub bump: u8,
+    /// Circuit breaker: strategy instructions fail while set
+    pub paused: bool,
+    /// Bump of the vault authority PDA, so strategies never search for it
+    pub vault_authority_bump: u8,
+    /// Flash loan lender program (Solend / SPL token-lending)
+    pub lending_program: Pubkey,
+    /// Lender reserves strategies may borrow from
+    #[max_len(MAX_ALLOWED_RESERVES)]
+    pub allowed_reserves: Vec<Pubkey>,
+    /// Whirlpools and OpenBook markets strategies may trade on
+    #[max_len(MAX_ALLOWED_POOLS)]
+    pub allowed_pools: Vec<Pubkey>,
+    /// Largest amount a single flash loan may borrow
+    pub max_borrow_amount: u64,
+    /// Profit floor applied on top of each strategy's own min_profit
+    pub min_profit: u64,
+    /// Wallet that owns the token accounts treasury moves pay into
+    pub treasury: Pubkey,
+    /// Share of each profit paid to the operator that ran the strategy
+    pub operator_reward_bps: u16,
+    /// Further profit shares, e.g. team treasury and insurance fund.
+    /// Whatever is not shared out stays in the vault.
+    #[max_len(MAX_BENEFICIARIES)]
+    pub beneficiaries: Vec<Beneficiary>,
+    /// Keys allowed to call strategy instructions
+    #[max_len(MAX_OPERATORS)]
+    pub operators: Vec<Pubkey>,
+    /// Keys allowed to move treasury funds
+    #[max_len(MAX_TREASURERS)]
+    pub treasurers: Vec<Pubkey>,
+}
+
+impl Config {
+    pub fn require_reserve(&self, reserve: &Pubkey) -> Result<()> {
+        require!(self.allowed_reserves.contains(reserve), ErrorCode::ReserveNotAllowed);
+        Ok(())
+    }
+
+    pub fn require_pool(&self, pool: &Pubkey) -> Result<()> {
+        require!(self.allowed_pools.contains(pool), ErrorCode::PoolNotAllowed);
+        Ok(())
+    }
+
+    pub fn require_borrow_amount(&self, amount: u64) -> Result<()> {
+        require_gte!(self.max_borrow_amount, amount, ErrorCode::BorrowLimitExceeded);
+        Ok(())
+    }
+
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        self.operators.contains(key)
+    }
+
+    pub fn is_treasurer(&self, key: &Pubkey) -> bool {
+        self.treasurers.contains(key)
+    }
+
+    fn members_mut(&mut self, role: Role) -> (&mut Vec<Pubkey>, usize) {
+        match role {
+            Role::Operator => (&mut self.operators, MAX_OPERATORS),
+            Role::Treasurer => (&mut self.treasurers, MAX_TREASURERS),
+        }
+    }
+
+    /// The stricter of the caller's min_profit and the configured floor
+    pub fn min_profit(&self, requested: u64) -> u64 {
+        requested.max(self.min_profit)
+    }
+
+    fn apply(&mut self, params: ConfigParams) -> Result<()> {
+        require_gte!(MAX_ALLOWED_RESERVES, params.allowed_reserves.len(), ErrorCode::ConfigListTooLong);
+        require_gte!(MAX_ALLOWED_POOLS, params.allowed_pools.len(), ErrorCode::ConfigListTooLong);
+        require_gte!(MAX_BENEFICIARIES, params.beneficiaries.len(), ErrorCode::ConfigListTooLong);
+        let total_bps = params
+            .beneficiaries
+            .iter()
+            .fold(params.operator_reward_bps as u64, |total, b| total + b.share_bps as u64);
+        require_gte!(BPS_DENOMINATOR, total_bps, ErrorCode::InvalidProfitShares);
+
+        self.lending_program = params.lending_program;
+        self.allowed_reserves = params.allowed_reserves;
+        self.allowed_pools = params.allowed_pools;
+        self.max_borrow_amount = params.max_borrow_amount;
+        self.min_profit = params.min_profit;
+        self.treasury = params.treasury;
+        self.operator_reward_bps = params.operator_reward_bps;
+        self.beneficiaries = params.beneficiaries;
+        Ok(())
+    }
+}
+
+/// Settable fields of the config, replaced wholesale on update
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub lending_program: Pubkey,
+    pub allowed_reserves: Vec<Pubkey>,
+    pub allowed_pools: Vec<Pubkey>,
+    pub max_borrow_amount: u64,
+    pub min_profit: u64,
+    pub treasury: Pubkey,
+    pub operator_reward_bps: u16,
+    pub beneficiaries: Vec<Beneficiary>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// Becomes the config admin; must hold the program's upgrade authority so
+    /// nobody can initialise the config ahead of the deployer
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::DegenLaunch>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize(&mut self, params: ConfigParams, bump: u8) -> Result<()> {
+        self.config.admin = self.admin.key();
+        self.config.bump = bump;
+        self.config.paused = false;
+        self.config.vault_authority_bump =
+            Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &crate::ID).1;
+        self.config.apply(params)
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    /// Replaces the settings and optionally hands the admin role to `new_admin`
+    pub fn update(&mut self, params: ConfigParams, new_admin: Option<Pubkey>) -> Result<()> {
+        if let Some(new_admin) = new_admin {
+            self.config.admin = new_admin;
+        }
+        self.config.apply(params)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.config.paused = paused;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ManageRole<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+impl<'info> ManageRole<'info> {
+    pub fn add_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let (members, max_len) = self.config.members_mut(role);
+        require!(!members.contains(&member), ErrorCode::RoleAlreadyAssigned);
+        require_gt!(max_len, members.len(), ErrorCode::ConfigListTooLong);
+        members.push(member);
+        Ok(())
+    }
+
+    pub fn remove_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let (members, _) = self.config.members_mut(role);
+        let index = members
+            .iter()
+            .position(|key| *key == member)
+            .ok_or(ErrorCode::RoleNotAssigned)?;
+        members.remove(index);
+        Ok(())
+    }
+}
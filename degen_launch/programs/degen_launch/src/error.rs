@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid Whirlpool account data.")]
+    InvalidWhirlpoolAccount,
+    #[msg("Invalid OpenBook market or book side account.")]
+    InvalidOpenbookAccount,
+    #[msg("Swap output is below the minimum amount out.")]
+    SlippageExceeded,
+    #[msg("Not enough remaining accounts for the swap venue.")]
+    MissingVenueAccounts,
+    #[msg("Token account does not match the venue's mints or vaults.")]
+    VenueAccountMismatch,
+    #[msg("Route steps are empty, too long or do not chain.")]
+    InvalidRoute,
+    #[msg("Strategy proceeds do not cover the repayment plus minimum profit.")]
+    InsufficientProfit,
+    #[msg("Signer is not allowed to perform this action.")]
+    Unauthorized,
+    #[msg("Reserve is not in the config allowlist.")]
+    ReserveNotAllowed,
+    #[msg("Pool or market is not in the config allowlist.")]
+    PoolNotAllowed,
+    #[msg("Borrow amount exceeds the configured limit.")]
+    BorrowLimitExceeded,
+    #[msg("Config list exceeds its maximum length.")]
+    ConfigListTooLong,
+    #[msg("Program is paused by the admin.")]
+    Paused,
+    #[msg("Member already holds this role.")]
+    RoleAlreadyAssigned,
+    #[msg("Member does not hold this role.")]
+    RoleNotAssigned,
+    #[msg("Withdrawal would leave the vault below its minimum working balance.")]
+    BelowMinWorkingBalance,
+    #[msg("Profit shares add up to more than 100%.")]
+    InvalidProfitShares,
+    #[msg("Profit share token account is missing or does not match its recipient.")]
+    InvalidProfitAccount,
+    // Lender and venue failures; translate_cpi_error maps failed CPIs onto these
+    // when a client decodes a transaction error
+    #[msg("Lending program rejected the flash loan.")]
+    LenderRejected,
+    #[msg("Whirlpool tick arrays are missing or out of sequence.")]
+    InvalidTickArrays,
+    #[msg("Swap amount is too small to trade.")]
+    SwapAmountTooSmall,
+    #[msg("Strategy instructions must be top-level, not invoked through CPI.")]
+    StrategyInvokedByCpi,
+    #[msg("Strategy instruction is not between the lender's flash borrow and repay.")]
+    NoActiveFlashLoan,
+    #[msg("Lender's flash borrow or repay does not match the strategy's loan.")]
+    FlashLoanMismatch,
+    #[msg("Reserve data is invalid or its liquidity supply does not match.")]
+    InvalidReserve,
+}
+
+/// External programs whose custom error codes we translate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CpiProgram {
+    Solend,
+    Whirlpool,
+}
+
+impl CpiProgram {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CpiProgram::Solend => "Solend",
+            CpiProgram::Whirlpool => "Whirlpool",
+        }
+    }
+}
+
+/// Maps a known custom error code of `program` to its name and our closest error.
+///
+/// Off-chain only: a failing CPI aborts the whole transaction with the callee's own
+/// code before control returns to us, so clients use this to decode those errors.
+pub fn translate_cpi_error(program: CpiProgram, code: u32) -> Option<(&'static str, ErrorCode)> {
+    let translated = match (program, code) {
+        // SPL token-lending LendingError, which Solend extends
+        (CpiProgram::Solend, 5) => ("InvalidAccountOwner", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 10) => ("InvalidAmount", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 13) => ("InvalidAccountInput", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 17) => ("TokenTransferFailed", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 20) => ("InsufficientLiquidity", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 22) => ("ReserveStale", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 26) => ("BorrowTooLarge", ErrorCode::BorrowLimitExceeded),
+        (CpiProgram::Solend, 43) => ("InvalidFlashLoanReceiverProgram", ErrorCode::LenderRejected),
+        (CpiProgram::Solend, 44) => ("NotEnoughLiquidityAfterFlashLoan", ErrorCode::LenderRejected),
+        // Whirlpool ErrorCode, Anchor numbering from 6000
+        (CpiProgram::Whirlpool, 6003) => ("TickArrayIndexOutofBounds", ErrorCode::InvalidTickArrays),
+        (CpiProgram::Whirlpool, 6017) => ("TokenMaxExceeded", ErrorCode::SlippageExceeded),
+        (CpiProgram::Whirlpool, 6018) => ("TokenMinSubceeded", ErrorCode::SlippageExceeded),
+        (CpiProgram::Whirlpool, 6023) => ("InvalidTickArraySequence", ErrorCode::InvalidTickArrays),
+        (CpiProgram::Whirlpool, 6035) => ("ZeroTradableAmount", ErrorCode::SwapAmountTooSmall),
+        (CpiProgram::Whirlpool, 6036) => ("AmountOutBelowMinimum", ErrorCode::SlippageExceeded),
+        (CpiProgram::Whirlpool, 6037) => ("AmountInAboveMaximum", ErrorCode::SlippageExceeded),
+        (CpiProgram::Whirlpool, 6038) => ("TickArraySequenceInvalidIndex", ErrorCode::InvalidTickArrays),
+        _ => return None,
+    };
+    Some(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translated(program: CpiProgram, code: u32) -> Option<(&'static str, u32)> {
+        translate_cpi_error(program, code).map(|(name, error)| (name, error as u32))
+    }
+
+    #[test]
+    fn translates_solend_errors() {
+        assert_eq!(
+            translated(CpiProgram::Solend, 44),
+            Some(("NotEnoughLiquidityAfterFlashLoan", ErrorCode::LenderRejected as u32))
+        );
+        assert_eq!(
+            translated(CpiProgram::Solend, 22),
+            Some(("ReserveStale", ErrorCode::LenderRejected as u32))
+        );
+    }
+
+    #[test]
+    fn translates_whirlpool_errors() {
+        assert_eq!(
+            translated(CpiProgram::Whirlpool, 6036),
+            Some(("AmountOutBelowMinimum", ErrorCode::SlippageExceeded as u32))
+        );
+        assert_eq!(
+            translated(CpiProgram::Whirlpool, 6023),
+            Some(("InvalidTickArraySequence", ErrorCode::InvalidTickArrays as u32))
+        );
+        assert_eq!(
+            translated(CpiProgram::Whirlpool, 6035),
+            Some(("ZeroTradableAmount", ErrorCode::SwapAmountTooSmall as u32))
+        );
+        assert_eq!(translated(CpiProgram::Whirlpool, 6034), None);
+    }
+
+    #[test]
+    fn codes_are_per_program() {
+        assert_eq!(translated(CpiProgram::Solend, 6036), None);
+        assert_eq!(translated(CpiProgram::Whirlpool, 44), None);
+        assert_eq!(translated(CpiProgram::Solend, 0), None);
+        assert_eq!(translated(CpiProgram::Whirlpool, u32::MAX), None);
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+// Emitted with `emit!` by default. Building with the `event-cpi` feature adds the
+// event authority accounts to the instructions that emit and sends events through a
+// self-CPI instead, so events survive log truncation.
+
+/// Emits events from an instruction handler through `emit_cpi!` or `emit!`,
+/// whichever the build uses. `ctx` is the handler's context, which `emit_cpi!`
+/// reads the event authority from.
+macro_rules! emit_events {
+    ($ctx:expr, $($event:expr),+ $(,)?) => {{
+        #[cfg(feature = "event-cpi")]
+        {
+            let ctx = &$ctx;
+            $(emit_cpi!($event);)+
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        {
+            $(emit!($event);)+
+        }
+    }};
+}
+
+#[event]
+pub struct FlashLoanStarted {
+    pub lending_program: Pubkey,
+    pub reserve: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub reserve_liquidity_supply: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ProfitRealized {
+    pub operator: Pubkey,
+    pub mint: Pubkey,
+    pub profit: u64,
+    /// Part of the profit paid out to the operator and beneficiaries
+    pub distributed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub treasurer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
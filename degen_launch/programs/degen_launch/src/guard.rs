@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::config::Config;
+use crate::error::ErrorCode;
+use crate::solend::{FlashBorrow, FlashRepay, ReserveInfo};
+
+/// The lender's flash loan a strategy instruction runs inside
+#[derive(Clone, Copy, Debug)]
+pub struct FlashLoan {
+    pub reserve: ReserveInfo,
+    pub amount: u64,
+    /// Owed on top of `amount`, read from the reserve
+    pub fee: u64,
+}
+
+impl FlashLoan {
+    /// Finds the loan around the running strategy instruction. Strategies run as
+    /// top-level instructions with the lender's flash borrow right before them and its
+    /// flash repay right after, so they only run inside a loan the same transaction
+    /// pays back. `instructions` is the instructions sysvar; the borrow has to lend
+    /// exactly `amount` from `reserve` into `loan_account`, and the repay return it.
+    pub fn load(
+        config: &Config,
+        instructions: &AccountInfo,
+        reserve: &AccountInfo,
+        liquidity_supply: &Pubkey,
+        loan_account: &Pubkey,
+        amount: u64,
+    ) -> Result<Self> {
+        // Through CPI the sysvar describes the caller's instruction, not this one
+        require_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT, ErrorCode::StrategyInvokedByCpi);
+
+        let current = load_current_index_checked(instructions)?;
+        let borrow_index = current.checked_sub(1).ok_or(ErrorCode::NoActiveFlashLoan)?;
+        let borrow = load_instruction_at_checked(borrow_index.into(), instructions)
+            .map_err(|_| ErrorCode::NoActiveFlashLoan)?;
+        let repay = load_instruction_at_checked(usize::from(current) + 1, instructions)
+            .map_err(|_| ErrorCode::NoActiveFlashLoan)?;
+        let terms = FlashBorrow {
+            amount,
+            source_liquidity: *liquidity_supply,
+            destination_liquidity: *loan_account,
+            reserve: reserve.key(),
+        };
+        check_flash_loan(&config.lending_program, &terms, borrow_index, &borrow, &repay)?;
+
+        let reserve = ReserveInfo::load(config, reserve, liquidity_supply)?;
+        Ok(Self {
+            reserve,
+            amount,
+            fee: reserve.flash_loan_fee(amount)?,
+        })
+    }
+}
+
+/// Checks `borrow`, at `borrow_index`, is the lender's flash borrow of exactly `terms`,
+/// and `repay` pays that same borrow back from its destination to its source
+pub fn check_flash_loan(
+    lending_program: &Pubkey,
+    terms: &FlashBorrow,
+    borrow_index: u16,
+    borrow: &Instruction,
+    repay: &Instruction,
+) -> Result<()> {
+    require_keys_eq!(borrow.program_id, *lending_program, ErrorCode::NoActiveFlashLoan);
+    require_keys_eq!(repay.program_id, *lending_program, ErrorCode::NoActiveFlashLoan);
+    let borrow = FlashBorrow::parse(borrow).ok_or(ErrorCode::NoActiveFlashLoan)?;
+    let repay = FlashRepay::parse(repay).ok_or(ErrorCode::NoActiveFlashLoan)?;
+
+    require!(borrow == *terms, ErrorCode::FlashLoanMismatch);
+    require_eq!(u16::from(repay.borrow_instruction_index), borrow_index, ErrorCode::FlashLoanMismatch);
+    require_eq!(repay.amount, terms.amount, ErrorCode::FlashLoanMismatch);
+    require_keys_eq!(repay.reserve, terms.reserve, ErrorCode::FlashLoanMismatch);
+    require_keys_eq!(repay.source_liquidity, terms.destination_liquidity, ErrorCode::FlashLoanMismatch);
+    require_keys_eq!(repay.destination_liquidity, terms.source_liquidity, ErrorCode::FlashLoanMismatch);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solend::{flash_borrow_reserve_liquidity_ix, flash_repay_reserve_liquidity_ix};
+
+    struct Loan {
+        lending_program: Pubkey,
+        terms: FlashBorrow,
+        lending_market: Pubkey,
+        fee_receiver: Pubkey,
+    }
+
+    impl Loan {
+        fn new() -> Self {
+            Self {
+                lending_program: Pubkey::new_unique(),
+                terms: FlashBorrow {
+                    amount: 5_000_000,
+                    source_liquidity: Pubkey::new_unique(),
+                    destination_liquidity: Pubkey::new_unique(),
+                    reserve: Pubkey::new_unique(),
+                },
+                lending_market: Pubkey::new_unique(),
+                fee_receiver: Pubkey::new_unique(),
+            }
+        }
+
+        fn borrow(&self, terms: &FlashBorrow) -> Instruction {
+            flash_borrow_reserve_liquidity_ix(
+                self.lending_program,
+                terms.amount,
+                terms.source_liquidity,
+                terms.destination_liquidity,
+                terms.reserve,
+                self.lending_market,
+                Pubkey::new_unique(),
+            )
+        }
+
+        fn repay(&self, terms: &FlashBorrow, borrow_index: u8) -> Instruction {
+            flash_repay_reserve_liquidity_ix(
+                self.lending_program,
+                terms.amount,
+                borrow_index,
+                terms.destination_liquidity,
+                terms.source_liquidity,
+                self.fee_receiver,
+                self.fee_receiver,
+                terms.reserve,
+                self.lending_market,
+                Pubkey::new_unique(),
+            )
+        }
+
+        fn check(&self, borrow: &Instruction, repay: &Instruction) -> Result<()> {
+            check_flash_loan(&self.lending_program, &self.terms, 2, borrow, repay)
+        }
+    }
+
+    fn assert_error(result: Result<()>, expected: ErrorCode) {
+        match result {
+            Err(Error::AnchorError(error)) => assert_eq!(error.error_code_number, u32::from(expected)),
+            other => panic!("expected {expected:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_the_matching_borrow_and_repay() {
+        let loan = Loan::new();
+        assert!(loan.check(&loan.borrow(&loan.terms), &loan.repay(&loan.terms, 2)).is_ok());
+    }
+
+    #[test]
+    fn rejects_another_program() {
+        let loan = Loan::new();
+        let mut borrow = loan.borrow(&loan.terms);
+        borrow.program_id = Pubkey::new_unique();
+        assert_error(loan.check(&borrow, &loan.repay(&loan.terms, 2)), ErrorCode::NoActiveFlashLoan);
+
+        let mut repay = loan.repay(&loan.terms, 2);
+        repay.program_id = Pubkey::new_unique();
+        assert_error(loan.check(&loan.borrow(&loan.terms), &repay), ErrorCode::NoActiveFlashLoan);
+    }
+
+    #[test]
+    fn rejects_other_lender_instructions() {
+        let loan = Loan::new();
+        let borrow = loan.borrow(&loan.terms);
+        assert_error(loan.check(&borrow, &borrow), ErrorCode::NoActiveFlashLoan);
+    }
+
+    #[test]
+    fn rejects_a_borrow_other_than_the_strategy_expects() {
+        let loan = Loan::new();
+        let repay = loan.repay(&loan.terms, 2);
+        let mismatches = [
+            FlashBorrow { amount: 4_999_999, ..loan.terms },
+            FlashBorrow { source_liquidity: Pubkey::new_unique(), ..loan.terms },
+            FlashBorrow { destination_liquidity: Pubkey::new_unique(), ..loan.terms },
+            FlashBorrow { reserve: Pubkey::new_unique(), ..loan.terms },
+        ];
+        for terms in mismatches {
+            assert_error(loan.check(&loan.borrow(&terms), &repay), ErrorCode::FlashLoanMismatch);
+        }
+    }
+
+    #[test]
+    fn rejects_a_repay_of_another_loan() {
+        let loan = Loan::new();
+        let borrow = loan.borrow(&loan.terms);
+        assert_error(loan.check(&borrow, &loan.repay(&loan.terms, 1)), ErrorCode::FlashLoanMismatch);
+
+        let mismatches = [
+            FlashBorrow { amount: 1, ..loan.terms },
+            FlashBorrow { source_liquidity: Pubkey::new_unique(), ..loan.terms },
+            FlashBorrow { destination_liquidity: Pubkey::new_unique(), ..loan.terms },
+            FlashBorrow { reserve: Pubkey::new_unique(), ..loan.terms },
+        ];
+        for terms in mismatches {
+            assert_error(loan.check(&borrow, &loan.repay(&terms, 2)), ErrorCode::FlashLoanMismatch);
+        }
+    }
+}
@@ -1,123 +1,371 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{instruction::{AccountMeta, Instruction}, program::invoke};
+use anchor_lang::solana_program::sysvar;
 use anchor_spl::token::{Token, TokenAccount, Mint};
-use whirlpool_cpi::state::{WhirlpoolRewardInfo, Whirlpool, TickArray, FeeTier};
-use crate::whirlpool_utils::WhirlpoolPdas;
+use anchor_lang::Discriminator;
+use whirlpool_cpi::state::{Whirlpool, FeeTier};
+use crate::error::ErrorCode;
 
+pub mod arbitrage;
+pub mod config;
+pub mod error;
+#[macro_use]
+pub mod events;
+pub mod guard;
+pub mod openbook;
+pub mod profit;
+pub mod receipt;
+pub mod route;
 pub mod solend;
-pub mod swap_via_orca;
-mod constants;
-mod whirlpool_utils;
+pub mod stats;
+pub mod treasury;
+pub mod vault;
+pub mod venue;
+pub mod whirlpool_utils;
 
-#[allow(unused_imports)]
-use solend::flash_loan_ix;
-#[allow(unused_imports)]
-use swap_via_orca::SwapViaOrca;
+pub use whirlpool_utils::WhirlpoolBumps;
 
+pub use arbitrage::*;
+pub use config::*;
+pub use events::*;
+pub use guard::*;
+pub use receipt::*;
+pub use route::*;
+pub use stats::*;
+pub use treasury::*;
+pub use vault::*;
+pub use venue::*;
 
-declare_id!("6UBFGLf5YBdVAzdzzoMhQsL3pM1KjgRp7EgVDCP4UqGV");
 
-#[derive(Accounts)]
-pub struct TriggerFlashloan<'info> {
-    /// SPL Token Lending program (Solend uses this under the hood)
-    pub lending_program: Program<'info, FlashLoanProgram>,
-    /// CHECK: This is validated by the lending program
-    pub reserve: AccountInfo<'info>,
-    /// CHECK: Not read by our program, just passed through
-    pub reserve_liquidity_supply: AccountInfo<'info>,
-    /// CHECK: Validated by Solend program
-    pub lending_market_authority: AccountInfo<'info>,
-    /// CHECK: This is the destination for borrowed funds
-    #[account(mut)]
-    pub user_liquidity: AccountInfo<'info>,
-    /// Token program
-    pub token_program: Program<'info, Token>,
-}
+declare_id!("6UBFGLf5YBdVAzdzzoMhQsL3pM1KjgRp7EgVDCP4UqGV");
 
 #[program]
 pub mod degen_launch {
     use super::*;
 
-    // Add this new function alongside your existing execute_flashloan_selfdump
-    pub fn start_flashloan(
-        ctx: Context<TriggerFlashloan>,
-        amount: u64,
+    /// Creates the config PDA, making the signer its admin
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.initialize(params, ctx.bumps.config)
+    }
+
+    /// Replaces the config settings; admin only
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        params: ConfigParams,
+        new_admin: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.update(params, new_admin)
+    }
+
+    /// Creates the program-owned vault token account and ledger for `mint`; admin only
+    pub fn initialize_vault(ctx: Context<InitializeVault>, min_working_balance: u64) -> Result<()> {
+        ctx.accounts.initialize(&ctx.bumps, min_working_balance)
+    }
+
+    /// Sets the balance treasury moves must leave in a vault; admin only
+    pub fn set_min_working_balance(
+        ctx: Context<SetMinWorkingBalance>,
+        min_working_balance: u64,
+    ) -> Result<()> {
+        ctx.accounts.set(min_working_balance)
+    }
+
+    /// Moves a vault's balance above its minimum working balance to the treasury; treasurer only
+    pub fn sweep_profits(ctx: Context<TreasuryTransfer>) -> Result<()> {
+        let event = ctx.accounts.sweep_profits()?;
+        emit_events!(ctx, event);
+        Ok(())
+    }
+
+    /// Moves `amount` from a vault to the treasury; treasurer only
+    pub fn withdraw(ctx: Context<TreasuryTransfer>, amount: u64) -> Result<()> {
+        let event = ctx.accounts.withdraw(amount)?;
+        emit_events!(ctx, event);
+        Ok(())
+    }
+
+    /// Stops every strategy instruction until unpaused; admin only
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        ctx.accounts.set_paused(true)
+    }
+
+    /// Lifts a pause; admin only
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        ctx.accounts.set_paused(false)
+    }
+
+    /// Grants `role` to `member`; admin only
+    pub fn add_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.add_role(role, member)
+    }
+
+    /// Revokes `role` from `member`; admin only
+    pub fn remove_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.remove_role(role, member)
+    }
+
+    /// Creates the statistics account of `strategy` on `pool`; admin only
+    pub fn initialize_strategy_stats(
+        ctx: Context<InitializeStrategyStats>,
+        strategy: Strategy,
+        pool: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.initialize(strategy, pool, ctx.bumps.stats)
+    }
+
+    /// Closes an archived execution receipt, returning its rent to the operator
+    pub fn close_execution_receipt(_ctx: Context<CloseExecutionReceipt>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Counts a failed strategy attempt, which could not record itself; operator only
+    pub fn record_failure(ctx: Context<RecordFailure>, kind: FailureKind) -> Result<()> {
+        ctx.accounts.stats.record_failure(kind)
+    }
+
+    /// Swaps between the program vaults on any supported venue, outside a flash loan;
+    /// operator only. remaining_accounts: the venue's accounts
+    pub fn swap_via_venue<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapViaVenue<'info>>,
+        venue: Venue,
+        amount_in: u64,
         minimum_amount_out: u64,
-        _is_buy: bool,
+        a_to_b: bool,
     ) -> Result<()> {
-        // Build the callback instruction that will be executed after receiving the flash loan
-        let accounts = vec![
-            AccountMeta::new(ctx.accounts.reserve.key(), false),
-            AccountMeta::new(ctx.accounts.user_liquidity.key(), false),
-            AccountMeta::new(ctx.accounts.reserve_liquidity_supply.key(), false),
-            AccountMeta::new(ctx.accounts.lending_market_authority.key(), false),
-            AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-            // Add all other required accounts for execute_flashloan_selfdump
-            // You'll need to pass these in from the client side
-        ];
-
-        let ix = Instruction {
-            program_id: crate::ID,
-            accounts,
-            data: anchor_lang::InstructionData::data(
-                &crate::instruction::ExecuteFlashloanSelfdump {
-                    amount,
-                    minimum_amount_out,
-                    _is_buy,
-                },
-            ),
+        let outcome = venue::execute_venue_swap(
+            &ctx.accounts.config,
+            venue,
+            ctx.remaining_accounts,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &[&vault::vault_signer_seeds(&[ctx.accounts.config.vault_authority_bump])],
+            amount_in,
+            minimum_amount_out,
+            a_to_b,
+        )?;
+
+        let swapped = SwapExecuted {
+            pool: outcome.pool,
+            input_mint: outcome.input_mint,
+            output_mint: outcome.output_mint,
+            amount_in: outcome.amount_in,
+            amount_out: outcome.amount_out,
+            slot: Clock::get()?.slot,
         };
+        emit_events!(ctx, swapped);
+
+        Ok(())
+    }
+
+    // Strategies below run as top-level instructions between the lender's flash
+    // borrow and its flash repay. Each one checks both through the instructions
+    // sysvar and approves the operator, who signs the repay, to pay the loan back.
+
+    /// Runs a list of swap legs that starts and ends in the loan vault, each leg's
+    /// output feeding the next, and sets the repayment aside.
+    /// remaining_accounts: each leg's venue accounts, then the operator token
+    /// account and one per configured beneficiary
+    pub fn execute_route<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteRoute<'info>>,
+        amount: u64,
+        steps: Vec<RouteStep>,
+        min_profit: u64,
+    ) -> Result<()> {
+        let outcome = ctx.accounts.execute(amount, &steps, min_profit, ctx.remaining_accounts)?;
 
-        // Construct Solend's flash loan instruction with our callback
-        let flashloan_ix = crate::solend::flash_loan_ix(
-            ctx.accounts.lending_program.key(),
-            ctx.accounts.reserve_liquidity_supply.key(),
-            ctx.accounts.user_liquidity.key(),
-            ctx.accounts.reserve.key(),
-            ctx.accounts.lending_market_authority.key(),
+        let slot = Clock::get()?.slot;
+        let started = FlashLoanStarted {
+            lending_program: ctx.accounts.config.lending_program,
+            reserve: ctx.accounts.reserve.key(),
+            destination: ctx.accounts.loan_account.key(),
             amount,
-        );
+            slot,
+        };
+        let repaid = FlashLoanRepaid {
+            reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.key(),
+            amount,
+            fee: outcome.flash_loan_fee,
+            slot,
+        };
+        let realized = ProfitRealized {
+            operator: ctx.accounts.operator.key(),
+            mint: ctx.accounts.loan_account.mint,
+            profit: outcome.profit,
+            distributed: outcome.distributed,
+            slot,
+        };
+        emit_events!(ctx, started, repaid, realized);
+
+        Ok(())
+    }
 
-        // Execute the flash loan via CPI
-        invoke(
-            &flashloan_ix,
-            &[
-                ctx.accounts.lending_program.to_account_info(),
-                ctx.accounts.reserve.clone(),
-                ctx.accounts.user_liquidity.clone(),
-                ctx.accounts.reserve_liquidity_supply.clone(),
-                ctx.accounts.lending_market_authority.clone(),
-                ctx.accounts.token_program.to_account_info(),
-            ],
+    /// Buys DEGEN on pool A, sells it on pool B and sets the repayment aside.
+    /// remaining_accounts: operator token account, then one per configured beneficiary.
+    /// Writes an execution receipt seeded with `receipt_nonce` when one is passed.
+    /// `bumps` are pool A's and pool B's oracle and tick array bumps, found off-chain
+    pub fn execute_round_trip_arbitrage<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteRoundTripArbitrage<'info>>,
+        amount: u64,
+        min_degen_out: u64,
+        min_usdc_out: u64,
+        min_profit: u64,
+        receipt_nonce: u64,
+        bumps: [WhirlpoolBumps; 2],
+    ) -> Result<()> {
+        let outcome = ctx.accounts.execute(
+            amount,
+            min_degen_out,
+            min_usdc_out,
+            min_profit,
+            &bumps,
+            ctx.remaining_accounts,
         )?;
 
+        let record = ExecutionReceipt {
+            operator: ctx.accounts.operator.key(),
+            nonce: receipt_nonce,
+            strategy: Strategy::RoundTrip,
+            pool: ctx.accounts.pool_a.whirlpool.key(),
+            mint: ctx.accounts.usdc_account.mint,
+            reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.key(),
+            amount,
+            flash_loan_fee: outcome.flash_loan_fee,
+            min_profit: ctx.accounts.config.min_profit(min_profit),
+            amount_out: outcome.usdc_received,
+            profit: outcome.profit,
+            distributed: outcome.distributed,
+            slot: Clock::get()?.slot,
+        };
+        receipt::write_receipt(&mut ctx.accounts.receipt, &record);
+
+        let started = FlashLoanStarted {
+            lending_program: ctx.accounts.config.lending_program,
+            reserve: ctx.accounts.reserve.key(),
+            destination: ctx.accounts.usdc_account.key(),
+            amount,
+            slot: record.slot,
+        };
+        let repaid = FlashLoanRepaid::from(&record);
+        let realized = ProfitRealized::from(&record);
+        emit_events!(ctx, started, repaid, realized);
+
         Ok(())
     }
 
-    // Your existing execute_flashloan_selfdump function remains here
-    pub fn execute_flashloan_selfdump(
-        ctx: Context<ExecuteFlashloanSelfdump>,
+    /// Cycles USDC through three pools and sets the repayment aside.
+    /// remaining_accounts: operator token account, then one per configured beneficiary.
+    /// Writes an execution receipt seeded with `receipt_nonce` when one is passed.
+    /// `bumps` are each pool's oracle and tick array bumps, found off-chain
+    pub fn execute_triangular_arbitrage<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteTriangularArbitrage<'info>>,
+        amount: u64,
+        min_profit: u64,
+        receipt_nonce: u64,
+        bumps: [WhirlpoolBumps; 3],
+    ) -> Result<()> {
+        let outcome = ctx.accounts.execute(amount, min_profit, &bumps, ctx.remaining_accounts)?;
+
+        let record = ExecutionReceipt {
+            operator: ctx.accounts.operator.key(),
+            nonce: receipt_nonce,
+            strategy: Strategy::Triangular,
+            pool: ctx.accounts.pool_1.whirlpool.key(),
+            mint: ctx.accounts.usdc_account.mint,
+            reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.key(),
+            amount,
+            flash_loan_fee: outcome.flash_loan_fee,
+            min_profit: ctx.accounts.config.min_profit(min_profit),
+            amount_out: outcome.usdc_received,
+            profit: outcome.profit,
+            distributed: outcome.distributed,
+            slot: Clock::get()?.slot,
+        };
+        receipt::write_receipt(&mut ctx.accounts.receipt, &record);
+
+        let started = FlashLoanStarted {
+            lending_program: ctx.accounts.config.lending_program,
+            reserve: ctx.accounts.reserve.key(),
+            destination: ctx.accounts.usdc_account.key(),
+            amount,
+            slot: record.slot,
+        };
+        let repaid = FlashLoanRepaid::from(&record);
+        let realized = ProfitRealized::from(&record);
+        emit_events!(ctx, started, repaid, realized);
+
+        Ok(())
+    }
+
+    /// Dumps token A of the whirlpool for token B and sets the repayment of the
+    /// borrowed token B aside out of the loan and the swap output. `bumps` are the
+    /// whirlpool's oracle and tick array bumps, found off-chain.
+    /// remaining_accounts: operator token account, then one per configured beneficiary.
+    /// Writes an execution receipt seeded with `receipt_nonce` when one is passed.
+    pub fn execute_flashloan_selfdump<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteFlashloanSelfdump<'info>>,
         amount: u64,
         minimum_amount_out: u64,
-        _is_buy: bool,
+        bumps: WhirlpoolBumps,
+        receipt_nonce: u64,
     ) -> Result<()> {
-        let whirlpool_account_info = &ctx.accounts.whirlpool;
-        let whirlpool_data = whirlpool_account_info.try_borrow_data()?;
+        let config = &ctx.accounts.config;
+        let flash_loan_fee = FlashLoan::load(
+            config,
+            &ctx.accounts.instructions,
+            &ctx.accounts.reserve,
+            &ctx.accounts.reserve_liquidity_supply.key(),
+            &ctx.accounts.user_liquidity.key(),
+            amount,
+        )?
+        .fee;
+        config.require_pool(&ctx.accounts.whirlpool.key())?;
+        config.require_borrow_amount(amount)?;
 
-        let fee_tier: u16 = 64;
+        // Read the whirlpool zero-copy and release the borrow before the CPI writes it
+        let tokens = {
+            let whirlpool_data = ctx.accounts.whirlpool.try_borrow_data()?;
+            // Offsets below are only meaningful on a Whirlpool, not another account the program owns
+            require!(
+                whirlpool_data.get(..8) == Some(&Whirlpool::DISCRIMINATOR[..]),
+                ErrorCode::InvalidWhirlpoolAccount
+            );
+            let tokens = whirlpool_utils::parse_tokens_from_whirlpool(&whirlpool_data)?;
 
-        let pdas = WhirlpoolPdas::new(
-            &whirlpool_data,
-            whirlpool_account_info.key(),
-            ctx.accounts.whirlpool_program.key(),
-            fee_tier,
-        )?;
+            require_keys_eq!(ctx.accounts.token_owner_account.mint, tokens.token_mint_a, ErrorCode::VenueAccountMismatch);
+            require_keys_eq!(ctx.accounts.user_liquidity.mint, tokens.token_mint_b, ErrorCode::VenueAccountMismatch);
+            require_keys_eq!(ctx.accounts.token_vault_a.key(), tokens.token_vault_a, ErrorCode::VenueAccountMismatch);
+            require_keys_eq!(ctx.accounts.token_vault_b.key(), tokens.token_vault_b, ErrorCode::VenueAccountMismatch);
+            require_keys_eq!(ctx.accounts.token_mint_a.key(), tokens.token_mint_a, ErrorCode::VenueAccountMismatch);
+            require_keys_eq!(ctx.accounts.token_mint_b.key(), tokens.token_mint_b, ErrorCode::VenueAccountMismatch);
+            require!(
+                ctx.accounts.fee_tier.whirlpools_config == whirlpool_utils::parse_whirlpools_config(&whirlpool_data)?
+                    && ctx.accounts.fee_tier.tick_spacing
+                        == whirlpool_utils::parse_tick_spacing_from_whirlpool(&whirlpool_data)?,
+                ErrorCode::InvalidWhirlpoolAccount
+            );
+
+            // Oracle and tick arrays must be the ones the swap walks from the current tick
+            whirlpool_utils::verify_swap_pdas(
+                &ctx.accounts.whirlpool_program.key(),
+                &ctx.accounts.whirlpool.key(),
+                &whirlpool_data,
+                true, // a_to_b, matches the swap below
+                &bumps,
+                &ctx.accounts.oracle.key(),
+                [
+                    Some(ctx.accounts.tick_array_0.key()),
+                    ctx.accounts.tick_array_1.as_ref().map(|a| a.key()),
+                    ctx.accounts.tick_array_2.as_ref().map(|a| a.key()),
+                ],
+            )?;
+
+            tokens
+        };
 
         // Construct the CPI Context for Whirlpool swap
         let cpi_accounts = whirlpool_cpi::cpi::accounts::Swap {
             whirlpool: ctx.accounts.whirlpool.to_account_info(),
             token_program: ctx.accounts.token_program.to_account_info(),
-            token_authority: ctx.accounts.token_authority.to_account_info(),
+            token_authority: ctx.accounts.vault_authority.to_account_info(),
             token_owner_account_a: ctx.accounts.token_owner_account.to_account_info(),
             token_vault_a: ctx.accounts.token_vault_a.to_account_info(),
             token_owner_account_b: ctx.accounts.user_liquidity.to_account_info(),
@@ -130,12 +378,21 @@ pub mod degen_launch {
 
         let cpi_program = ctx.accounts.whirlpool_program.to_account_info();
 
-        // Create the CPI Context
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        // Create the CPI Context, signed by the vault authority PDA
+        let bump = [ctx.accounts.config.vault_authority_bump];
+        let seeds = vault::vault_signer_seeds(&bump);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
         // Set swap parameters
         let sqrt_price_limit = 0; // 0 for no limit
 
+        let balance_before = ctx.accounts.user_liquidity.amount;
+        // What the vault held before the loan landed in it
+        let balance_before_loan = balance_before
+            .checked_sub(amount)
+            .ok_or(ErrorCode::FlashLoanMismatch)?;
+
         // Execute the swap via CPI
         whirlpool_cpi::cpi::swap(
             cpi_ctx,
@@ -146,80 +403,155 @@ pub mod degen_launch {
             true,  // a_to_b (direction of swap)
         )?;
 
+        ctx.accounts.user_liquidity.reload()?;
+        let amount_out = ctx.accounts.user_liquidity.amount.saturating_sub(balance_before);
+
+        // Repaid out of the loan plus the swap output; what stays is the vault's gain in token B
+        let profit = approve_repayment(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            signer_seeds,
+            &ctx.accounts.user_liquidity,
+            &ctx.accounts.operator.to_account_info(),
+            amount,
+            flash_loan_fee,
+            ctx.accounts.user_liquidity.amount.saturating_sub(balance_before_loan),
+            0,
+        )?;
+
+        let distributed = profit::distribute_profit(
+            &ctx.accounts.config,
+            &ctx.accounts.operator.key(),
+            profit,
+            &ctx.accounts.user_liquidity,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+
+        ctx.accounts.stats.record_success(amount, flash_loan_fee, profit)?;
+
+        let record = ExecutionReceipt {
+            operator: ctx.accounts.operator.key(),
+            nonce: receipt_nonce,
+            strategy: Strategy::Selfdump,
+            pool: ctx.accounts.whirlpool.key(),
+            mint: tokens.token_mint_b,
+            reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.key(),
+            amount,
+            flash_loan_fee,
+            min_profit: 0,
+            amount_out,
+            profit,
+            distributed,
+            slot: Clock::get()?.slot,
+        };
+        receipt::write_receipt(&mut ctx.accounts.receipt, &record);
+
+        let swapped = SwapExecuted {
+            pool: ctx.accounts.whirlpool.key(),
+            input_mint: tokens.token_mint_a,
+            output_mint: tokens.token_mint_b,
+            amount_in: amount,
+            amount_out,
+            slot: record.slot,
+        };
+        let started = FlashLoanStarted {
+            lending_program: ctx.accounts.config.lending_program,
+            reserve: ctx.accounts.reserve.key(),
+            destination: ctx.accounts.user_liquidity.key(),
+            amount,
+            slot: record.slot,
+        };
+        emit_events!(
+            ctx,
+            started,
+            swapped,
+            FlashLoanRepaid::from(&record),
+            ProfitRealized::from(&record),
+        );
+
         Ok(())
     }
 }
 
-// Update your ExecuteFlashloanSelfdump struct
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
+#[instruction(amount: u64, minimum_amount_out: u64, bumps: WhirlpoolBumps, receipt_nonce: u64)]
 pub struct ExecuteFlashloanSelfdump<'info> {
-    // Solend Flash Loan Accounts
-    /// SPL Token Lending program (Solend uses this under the hood)
-    pub lending_program: Program<'info, FlashLoanProgram>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, constraint = !config.paused @ ErrorCode::Paused)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator running the strategy, pays for the receipt if one is written
+    #[account(mut, constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
 
+    // Solend Flash Loan Accounts
     /// Reserve from which to borrow (USDC reserve)
-    /// CHECK: This is validated by the lending program
+    /// CHECK: Owned by the lender, contents validated by the lending program
+    #[account(owner = config.lending_program @ ErrorCode::ReserveNotAllowed)]
     pub reserve: AccountInfo<'info>,
 
-    /// Flashloan fee receiver
-    /// CHECK: Not read by our program, just passed through
-    pub reserve_liquidity_supply: AccountInfo<'info>,
+    /// Reserve liquidity the loan is drawn from and repaid to, checked against the reserve
+    #[account(token::mint = user_liquidity.mint)]
+    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
 
-    /// Lending market authority
-    /// CHECK: Validated by Solend program
-    pub lending_market_authority: AccountInfo<'info>,
+    /// CHECK: Instructions sysvar, read for the lender's borrow and repay around this one
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 
-    /// Destination for borrowed USDC
-    #[account(mut)]
-    pub user_liquidity: AccountInfo<'info>,
+    /// Destination for borrowed USDC, receives token B from the swap
+    #[account(mut, token::authority = vault_authority)]
+    pub user_liquidity: Box<Account<'info, TokenAccount>>,
 
     // Orca Swap Accounts
-    /// CHECK: Verified in CPI
-    pub whirlpool_program: Program<'info, Whirlpool>,  // Changed from AccountInfo to Program
-    /// CHECK: Verified in CPI
-    #[account(signer)]
-    pub token_authority: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
+    pub whirlpool_program: Program<'info, whirlpool_cpi::program::Whirlpool>,
+    /// CHECK: Program vault authority PDA, signs the swap
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    // Mints and vaults below are checked against the whirlpool's data in the handler
+    /// Vault spending token A
+    #[account(mut, token::authority = vault_authority)]
+    pub token_owner_account: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub token_owner_account: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    #[account(mut)]
-    pub token_vault_a: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    #[account(mut)]
-    pub token_vault_b: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    pub token_mint_a: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    pub token_mint_b: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+    pub token_mint_a: Box<Account<'info, Mint>>,
+    pub token_mint_b: Box<Account<'info, Mint>>,
+    /// CHECK: Owner checked here, read zero-copy in the handler
+    #[account(mut, owner = whirlpool_cpi::ID @ ErrorCode::InvalidWhirlpoolAccount)]
     pub whirlpool: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    #[account(mut)]
+    /// CHECK: Owner checked here, checked against the tick array PDAs in the handler
+    #[account(mut, owner = whirlpool_cpi::ID @ ErrorCode::InvalidWhirlpoolAccount)]
     pub tick_array_0: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
+    /// CHECK: Checked against the whirlpool's oracle PDA in the handler
     pub oracle: AccountInfo<'info>,
     /// Token program
-    /// CHECK: Used in CPI
-    pub token_program: AccountInfo<'info>,  // Changed from Program<'info, Token>
-    /// CHECK: Verified in CPI
-    #[account(mut)]
-    pub fee_tier: AccountInfo<'info>,
-    /// CHECK: Verified in CPI
-    #[account(mut)]
+    pub token_program: Program<'info, Token>,
+    /// Fee tier of the whirlpool's config and tick spacing
+    pub fee_tier: Box<Account<'info, FeeTier>>,
+    /// CHECK: Owner checked here, checked against the tick array PDAs in the handler
+    #[account(mut, owner = whirlpool_cpi::ID @ ErrorCode::InvalidWhirlpoolAccount)]
     pub tick_array_1: Option<AccountInfo<'info>>,
-    /// CHECK: Verified in CPI
-    #[account(mut)]
+    /// CHECK: Owner checked here, checked against the tick array PDAs in the handler
+    #[account(mut, owner = whirlpool_cpi::ID @ ErrorCode::InvalidWhirlpoolAccount)]
     pub tick_array_2: Option<AccountInfo<'info>>,
+    /// Statistics of this strategy on the whirlpool
+    #[account(
+        mut,
+        seeds = [STRATEGY_STATS_SEED, Strategy::Selfdump.seed(), whirlpool.key().as_ref()],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, StrategyStats>,
+    /// Optional durable record of this run
+    #[account(
+        init,
+        payer = operator,
+        space = 8 + ExecutionReceipt::INIT_SPACE,
+        seeds = [EXECUTION_RECEIPT_SEED, operator.key().as_ref(), &receipt_nonce.to_le_bytes()],
+        bump,
+    )]
+    pub receipt: Option<Box<Account<'info, ExecutionReceipt>>>,
+    pub system_program: Program<'info, System>,
 }
-
-#[derive(Clone)]
-pub struct FlashLoanProgram;
-
-impl anchor_lang::Id for FlashLoanProgram {
-    fn id() -> Pubkey {
-        use std::str::FromStr;
-        Pubkey::from_str("LendZqTs7gn5CTSJU1jWKhKuVpjJGom45nnwPb2AMTi").unwrap()
-    }
-}
\ No newline at end of file
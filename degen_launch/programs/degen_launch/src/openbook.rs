@@ -0,0 +1,630 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use std::str::FromStr;
+
+use crate::error::ErrorCode;
+
+pub const OPENBOOK_V2_PROGRAM_ID: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+
+// sha256("global:place_take_order")[..8]
+pub const PLACE_TAKE_ORDER_IX: [u8; 8] = [0x03, 0x2c, 0x47, 0x03, 0x1a, 0xc7, 0xcb, 0x55];
+
+// Market account layout (offsets include the 8 byte discriminator)
+const MARKET_BIDS_OFFSET: usize = 200;
+const MARKET_ASKS_OFFSET: usize = 232;
+const MARKET_EVENT_HEAP_OFFSET: usize = 264;
+const MARKET_QUOTE_LOT_SIZE_OFFSET: usize = 736;
+const MARKET_BASE_LOT_SIZE_OFFSET: usize = 744;
+const MARKET_TAKER_FEE_OFFSET: usize = 776;
+const MARKET_BASE_MINT_OFFSET: usize = 864;
+const MARKET_QUOTE_MINT_OFFSET: usize = 896;
+const MARKET_BASE_VAULT_OFFSET: usize = 928;
+const MARKET_QUOTE_VAULT_OFFSET: usize = 968;
+const MARKET_LEN: usize = 1136;
+
+// BookSide account layout
+const BOOKSIDE_FIXED_ROOT_OFFSET: usize = 8;
+const BOOKSIDE_NODES_OFFSET: usize = 840;
+const BOOKSIDE_MAX_NODES: usize = 1024;
+const NODE_SIZE: usize = 88;
+
+const NODE_TAG_INNER: u8 = 1;
+const NODE_TAG_LEAF: u8 = 2;
+
+// Taker fees are expressed in parts per million
+const FEE_PPM: i128 = 1_000_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceOrderType {
+    Limit,
+    ImmediateOrCancel,
+    PostOnly,
+    Market,
+    PostOnlySlide,
+    FillOrKill,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlaceTakeOrderArgs {
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots_including_fees: i64,
+    pub order_type: PlaceOrderType,
+    pub limit: u8,
+}
+
+/// Market parameters needed to size a taker order and quote it against the book
+pub struct MarketInfo {
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_heap: Pubkey,
+    pub base_lot_size: i64,
+    pub quote_lot_size: i64,
+    pub taker_fee: i64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+}
+
+impl MarketInfo {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < MARKET_LEN {
+            return Err(ErrorCode::InvalidOpenbookAccount.into());
+        }
+
+        let base_lot_size = read_i64(data, MARKET_BASE_LOT_SIZE_OFFSET);
+        let quote_lot_size = read_i64(data, MARKET_QUOTE_LOT_SIZE_OFFSET);
+        if base_lot_size <= 0 || quote_lot_size <= 0 {
+            return Err(ErrorCode::InvalidOpenbookAccount.into());
+        }
+
+        Ok(Self {
+            bids: read_pubkey(data, MARKET_BIDS_OFFSET),
+            asks: read_pubkey(data, MARKET_ASKS_OFFSET),
+            event_heap: read_pubkey(data, MARKET_EVENT_HEAP_OFFSET),
+            base_lot_size,
+            quote_lot_size,
+            taker_fee: read_i64(data, MARKET_TAKER_FEE_OFFSET),
+            base_mint: read_pubkey(data, MARKET_BASE_MINT_OFFSET),
+            quote_mint: read_pubkey(data, MARKET_QUOTE_MINT_OFFSET),
+            base_vault: read_pubkey(data, MARKET_BASE_VAULT_OFFSET),
+            quote_vault: read_pubkey(data, MARKET_QUOTE_VAULT_OFFSET),
+        })
+    }
+}
+
+/// A resting order on one side of the book, in lots
+#[derive(Clone, Copy)]
+pub struct BookLevel {
+    pub price_lots: i64,
+    pub quantity_lots: i64,
+}
+
+/// Walks the fixed-price order tree of a BookSide account in best-price order.
+/// Bids are visited highest first, asks lowest first.
+pub fn walk_book(data: &[u8], side: Side, max_levels: usize) -> Result<Vec<BookLevel>> {
+    if data.len() < BOOKSIDE_NODES_OFFSET + BOOKSIDE_MAX_NODES * NODE_SIZE {
+        return Err(ErrorCode::InvalidOpenbookAccount.into());
+    }
+
+    let root = read_u32(data, BOOKSIDE_FIXED_ROOT_OFFSET);
+    let leaf_count = read_u32(data, BOOKSIDE_FIXED_ROOT_OFFSET + 4);
+
+    let mut levels = Vec::new();
+    if leaf_count == 0 {
+        return Ok(levels);
+    }
+
+    // Crit-bit tree: children[0] holds the lower keys. The best bid is the
+    // highest key and the best ask the lowest, so bids descend right first.
+    let (near, far) = match side {
+        Side::Bid => (1, 0),
+        Side::Ask => (0, 1),
+    };
+
+    let mut stack = vec![root];
+    while let Some(index) = stack.pop() {
+        if levels.len() >= max_levels {
+            break;
+        }
+        let index = index as usize;
+        if index >= BOOKSIDE_MAX_NODES {
+            return Err(ErrorCode::InvalidOpenbookAccount.into());
+        }
+
+        let node = &data[BOOKSIDE_NODES_OFFSET + index * NODE_SIZE..][..NODE_SIZE];
+        match node[0] {
+            NODE_TAG_INNER => {
+                let children = [read_u32(node, 24), read_u32(node, 28)];
+                // Push the far child first so the near child is visited next
+                stack.push(children[far]);
+                stack.push(children[near]);
+            }
+            NODE_TAG_LEAF => {
+                let key = u128::from_le_bytes(node[8..24].try_into().unwrap());
+                levels.push(BookLevel {
+                    price_lots: (key >> 64) as i64,
+                    quantity_lots: read_i64(node, 56),
+                });
+            }
+            _ => return Err(ErrorCode::InvalidOpenbookAccount.into()),
+        }
+    }
+
+    Ok(levels)
+}
+
+/// Quotes a taker order of `amount_in` native tokens against the opposite book side.
+/// A bid spends quote tokens for base, an ask sells base tokens for quote.
+/// Returns the native amount received after taker fees.
+pub fn quote_take(
+    market: &MarketInfo,
+    levels: &[BookLevel],
+    side: Side,
+    amount_in: u64,
+) -> u64 {
+    let base_lot_size = market.base_lot_size as i128;
+    let quote_lot_size = market.quote_lot_size as i128;
+    let fee = market.taker_fee as i128;
+
+    let mut remaining = amount_in as i128;
+    let mut amount_out: i128 = 0;
+
+    for level in levels {
+        let price_lots = level.price_lots as i128;
+        let quantity_lots = level.quantity_lots as i128;
+        if price_lots <= 0 || quantity_lots <= 0 {
+            continue;
+        }
+
+        match side {
+            Side::Bid => {
+                // Quote cost of one base lot at this level, including fees
+                let lot_cost = price_lots * quote_lot_size * (FEE_PPM + fee) / FEE_PPM;
+                if lot_cost <= 0 {
+                    continue;
+                }
+                let fill = quantity_lots.min(remaining / lot_cost);
+                remaining -= fill * lot_cost;
+                amount_out += fill * base_lot_size;
+                if fill < quantity_lots {
+                    break;
+                }
+            }
+            Side::Ask => {
+                let fill = quantity_lots.min(remaining / base_lot_size);
+                remaining -= fill * base_lot_size;
+                amount_out += fill * price_lots * quote_lot_size * (FEE_PPM - fee) / FEE_PPM;
+                if fill < quantity_lots {
+                    break;
+                }
+            }
+        }
+    }
+
+    amount_out.max(0).min(u64::MAX as i128) as u64
+}
+
+impl MarketInfo {
+    /// Parses the market and checks the program and book accounts passed alongside it
+    pub fn load(
+        openbook_program: &AccountInfo,
+        market: &AccountInfo,
+        bids: &AccountInfo,
+        asks: &AccountInfo,
+        event_heap: &AccountInfo,
+    ) -> Result<Self> {
+        require_keys_eq!(
+            openbook_program.key(),
+            Pubkey::from_str(OPENBOOK_V2_PROGRAM_ID).unwrap(),
+            ErrorCode::InvalidOpenbookAccount
+        );
+        require_keys_eq!(*market.owner, openbook_program.key(), ErrorCode::InvalidOpenbookAccount);
+
+        let info = Self::parse(&market.try_borrow_data()?)?;
+        require_keys_eq!(bids.key(), info.bids, ErrorCode::InvalidOpenbookAccount);
+        require_keys_eq!(asks.key(), info.asks, ErrorCode::InvalidOpenbookAccount);
+        require_keys_eq!(event_heap.key(), info.event_heap, ErrorCode::InvalidOpenbookAccount);
+
+        Ok(info)
+    }
+}
+
+/// Quotes a take order by walking the side of the book it would fill against
+pub fn quote_from_book<'info>(
+    market: &MarketInfo,
+    bids: &AccountInfo<'info>,
+    asks: &AccountInfo<'info>,
+    amount_in: u64,
+    is_bid: bool,
+) -> Result<u64> {
+    // A bid takes from the asks and an ask takes from the bids
+    let (side, book, book_side) = if is_bid {
+        (Side::Bid, asks, Side::Ask)
+    } else {
+        (Side::Ask, bids, Side::Bid)
+    };
+    let levels = walk_book(&book.try_borrow_data()?, book_side, 64)?;
+    Ok(quote_take(market, &levels, side, amount_in))
+}
+
+/// Sizes a market taker order that spends exactly `amount_in` native tokens, with a
+/// limit price no worse than one at which a full fill still pays `minimum_amount_out`
+pub fn take_order_args(
+    market: &MarketInfo,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    side: Side,
+) -> Result<PlaceTakeOrderArgs> {
+    let base_lot_size = market.base_lot_size as i128;
+    let quote_lot_size = market.quote_lot_size as i128;
+    let fee = market.taker_fee as i128;
+    let min_out = minimum_amount_out as i128;
+
+    let args = match side {
+        Side::Bid => {
+            let quote_lots = amount_in as i128 / quote_lot_size;
+            if quote_lots == 0 {
+                msg!("Bid of {} quote is below the {} quote lot size", amount_in, quote_lot_size);
+                return Err(ErrorCode::SwapAmountTooSmall.into());
+            }
+            // Highest price whose lots, fees included, the budget still buys min-out of
+            let price_lots = if min_out == 0 {
+                i64::MAX as i128
+            } else {
+                let min_base_lots = (min_out + base_lot_size - 1) / base_lot_size;
+                quote_lots * FEE_PPM / ((FEE_PPM + fee) * min_base_lots)
+            };
+            PlaceTakeOrderArgs {
+                side,
+                price_lots: limit_price(price_lots)?,
+                max_base_lots: i64::MAX,
+                max_quote_lots_including_fees: quote_lots as i64,
+                order_type: PlaceOrderType::Market,
+                limit: 10,
+            }
+        }
+        Side::Ask => {
+            let base_lots = amount_in as i128 / base_lot_size;
+            if base_lots == 0 {
+                msg!("Ask of {} base is below the {} base lot size", amount_in, base_lot_size);
+                return Err(ErrorCode::SwapAmountTooSmall.into());
+            }
+            // Lowest price at which selling every lot, less fees, still pays min-out
+            let price_lots = if min_out == 0 {
+                1
+            } else {
+                let proceeds_per_price_lot = base_lots * quote_lot_size * (FEE_PPM - fee);
+                require_gt!(proceeds_per_price_lot, 0, ErrorCode::SlippageExceeded);
+                (min_out * FEE_PPM + proceeds_per_price_lot - 1) / proceeds_per_price_lot
+            };
+            PlaceTakeOrderArgs {
+                side,
+                price_lots: limit_price(price_lots.max(1))?,
+                max_base_lots: base_lots as i64,
+                max_quote_lots_including_fees: i64::MAX,
+                order_type: PlaceOrderType::Market,
+                limit: 10,
+            }
+        }
+    };
+    Ok(args)
+}
+
+/// A limit price the market accepts, or SlippageExceeded when min-out rules out every price
+fn limit_price(price_lots: i128) -> Result<i64> {
+    require_gt!(price_lots, 0, ErrorCode::SlippageExceeded);
+    i64::try_from(price_lots).map_err(|_| ErrorCode::SlippageExceeded.into())
+}
+
+pub struct TakeOrderKeys {
+    pub program_id: Pubkey,
+    pub signer: Pubkey,
+    pub penalty_payer: Pubkey,
+    pub market: Pubkey,
+    pub market_authority: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub event_heap: Pubkey,
+    pub user_base_account: Pubkey,
+    pub user_quote_account: Pubkey,
+    pub oracle_a: Pubkey,
+    pub oracle_b: Pubkey,
+    pub token_program: Pubkey,
+    pub system_program: Pubkey,
+}
+
+pub fn place_take_order_ix(keys: &TakeOrderKeys, args: &PlaceTakeOrderArgs) -> Result<Instruction> {
+    let accounts = vec![
+        AccountMeta::new(keys.signer, true),
+        AccountMeta::new(keys.penalty_payer, true),
+        AccountMeta::new(keys.market, false),
+        AccountMeta::new_readonly(keys.market_authority, false),
+        AccountMeta::new(keys.bids, false),
+        AccountMeta::new(keys.asks, false),
+        AccountMeta::new(keys.market_base_vault, false),
+        AccountMeta::new(keys.market_quote_vault, false),
+        AccountMeta::new(keys.event_heap, false),
+        AccountMeta::new(keys.user_base_account, false),
+        AccountMeta::new(keys.user_quote_account, false),
+        AccountMeta::new_readonly(keys.oracle_a, false),
+        AccountMeta::new_readonly(keys.oracle_b, false),
+        AccountMeta::new_readonly(keys.token_program, false),
+        AccountMeta::new_readonly(keys.system_program, false),
+        // open_orders_admin is unset for permissionless markets
+        AccountMeta::new_readonly(keys.program_id, false),
+    ];
+
+    let mut data = PLACE_TAKE_ORDER_IX.to_vec();
+    data.extend_from_slice(&args.try_to_vec()?);
+
+    Ok(Instruction {
+        program_id: keys.program_id,
+        accounts,
+        data,
+    })
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKSIDE_LEN: usize = BOOKSIDE_NODES_OFFSET + BOOKSIDE_MAX_NODES * NODE_SIZE;
+
+    fn node_mut(data: &mut [u8], index: usize) -> &mut [u8] {
+        &mut data[BOOKSIDE_NODES_OFFSET + index * NODE_SIZE..][..NODE_SIZE]
+    }
+
+    fn inner(data: &mut [u8], index: usize, children: [u32; 2]) {
+        let node = node_mut(data, index);
+        node[0] = NODE_TAG_INNER;
+        node[24..28].copy_from_slice(&children[0].to_le_bytes());
+        node[28..32].copy_from_slice(&children[1].to_le_bytes());
+    }
+
+    fn leaf(data: &mut [u8], index: usize, price_lots: i64, seq: u64, quantity_lots: i64) {
+        let node = node_mut(data, index);
+        node[0] = NODE_TAG_LEAF;
+        let key = (price_lots as u128) << 64 | seq as u128;
+        node[8..24].copy_from_slice(&key.to_le_bytes());
+        node[56..64].copy_from_slice(&quantity_lots.to_le_bytes());
+    }
+
+    /// root(0) -> [leaf(1) @ 100, inner(2) -> [leaf(3) @ 105, leaf(4) @ 110]]
+    fn book() -> Vec<u8> {
+        let mut data = vec![0u8; BOOKSIDE_LEN];
+        data[BOOKSIDE_FIXED_ROOT_OFFSET..][..4].copy_from_slice(&0u32.to_le_bytes());
+        data[BOOKSIDE_FIXED_ROOT_OFFSET + 4..][..4].copy_from_slice(&3u32.to_le_bytes());
+        inner(&mut data, 0, [1, 2]);
+        leaf(&mut data, 1, 100, 7, 5);
+        inner(&mut data, 2, [3, 4]);
+        leaf(&mut data, 3, 105, 8, 6);
+        leaf(&mut data, 4, 110, 9, 7);
+        data
+    }
+
+    fn prices(levels: &[BookLevel]) -> Vec<i64> {
+        levels.iter().map(|level| level.price_lots).collect()
+    }
+
+    fn market(taker_fee: i64) -> MarketInfo {
+        MarketInfo {
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+            event_heap: Pubkey::new_unique(),
+            base_lot_size: 100,
+            quote_lot_size: 10,
+            taker_fee,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+        }
+    }
+
+    /// A Market account written field by field in the program's declaration order,
+    /// so the offsets above are checked against the struct rather than restated
+    struct MarketWriter(Vec<u8>);
+
+    impl MarketWriter {
+        fn skip(&mut self, len: usize) -> &mut Self {
+            self.0.resize(self.0.len() + len, 0xaa);
+            self
+        }
+
+        fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+            self.0.extend_from_slice(bytes);
+            self
+        }
+    }
+
+    struct MarketFixture {
+        data: Vec<u8>,
+        bids: Pubkey,
+        asks: Pubkey,
+        event_heap: Pubkey,
+        base_mint: Pubkey,
+        quote_mint: Pubkey,
+        base_vault: Pubkey,
+        quote_vault: Pubkey,
+    }
+
+    fn market_fixture() -> MarketFixture {
+        let [bids, asks, event_heap, base_mint, quote_mint, base_vault, quote_vault] =
+            [(); 7].map(|_| Pubkey::new_unique());
+        let mut market = MarketWriter(Vec::new());
+        market
+            .skip(8) // discriminator
+            .skip(1 + 1 + 1 + 5) // bump, base_decimals, quote_decimals, padding
+            .skip(32 + 8 + 32) // market_authority, time_expiry, collect_fee_admin
+            .skip(32 * 3) // open_orders_admin, consume_events_admin, close_market_admin
+            .skip(16) // name
+            .bytes(bids.as_ref())
+            .bytes(asks.as_ref())
+            .bytes(event_heap.as_ref())
+            .skip(32 * 2) // oracle_a, oracle_b
+            .skip(8 + 8 + 72) // oracle_config
+            .skip(8 + 8 + 24 * 8 + 8 + 4 + 4 + 4 + 4 + 1 + 1 + 6 + 48) // stable_price_model
+            .bytes(&10i64.to_le_bytes()) // quote_lot_size
+            .bytes(&1_000_000i64.to_le_bytes()) // base_lot_size
+            .skip(8 + 8) // seq_num, registration_time
+            .bytes(&(-200i64).to_le_bytes()) // maker_fee
+            .bytes(&400i64.to_le_bytes()) // taker_fee
+            .skip(16 + 16 + 8 + 8 + 16 + 16) // fee and volume counters
+            .bytes(base_mint.as_ref())
+            .bytes(quote_mint.as_ref())
+            .bytes(base_vault.as_ref())
+            .skip(8) // base_deposit_total
+            .bytes(quote_vault.as_ref())
+            .skip(8) // quote_deposit_total
+            .skip(128); // reserved
+        MarketFixture {
+            data: market.0,
+            bids,
+            asks,
+            event_heap,
+            base_mint,
+            quote_mint,
+            base_vault,
+            quote_vault,
+        }
+    }
+
+    fn level(price_lots: i64, quantity_lots: i64) -> BookLevel {
+        BookLevel { price_lots, quantity_lots }
+    }
+
+    #[test]
+    fn parses_market_layout() {
+        let fixture = market_fixture();
+        assert_eq!(fixture.data.len(), MARKET_LEN);
+
+        let market = MarketInfo::parse(&fixture.data).unwrap();
+        assert_eq!(market.bids, fixture.bids);
+        assert_eq!(market.asks, fixture.asks);
+        assert_eq!(market.event_heap, fixture.event_heap);
+        assert_eq!(market.quote_lot_size, 10);
+        assert_eq!(market.base_lot_size, 1_000_000);
+        assert_eq!(market.taker_fee, 400);
+        assert_eq!(market.base_mint, fixture.base_mint);
+        assert_eq!(market.quote_mint, fixture.quote_mint);
+        assert_eq!(market.base_vault, fixture.base_vault);
+        assert_eq!(market.quote_vault, fixture.quote_vault);
+
+        assert!(MarketInfo::parse(&fixture.data[..MARKET_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn walks_asks_lowest_first() {
+        let levels = walk_book(&book(), Side::Ask, 10).unwrap();
+        assert_eq!(prices(&levels), [100, 105, 110]);
+        assert_eq!(levels.iter().map(|l| l.quantity_lots).collect::<Vec<_>>(), [5, 6, 7]);
+    }
+
+    #[test]
+    fn walks_bids_highest_first() {
+        let levels = walk_book(&book(), Side::Bid, 10).unwrap();
+        assert_eq!(prices(&levels), [110, 105, 100]);
+    }
+
+    #[test]
+    fn walk_stops_at_max_levels() {
+        assert_eq!(prices(&walk_book(&book(), Side::Bid, 2).unwrap()), [110, 105]);
+        assert!(walk_book(&book(), Side::Ask, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn walks_empty_book() {
+        let data = vec![0u8; BOOKSIDE_LEN];
+        assert!(walk_book(&data, Side::Ask, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_books() {
+        assert!(walk_book(&book()[..BOOKSIDE_LEN - 1], Side::Ask, 10).is_err());
+
+        let mut bad_tag = book();
+        node_mut(&mut bad_tag, 3)[0] = 9;
+        assert!(walk_book(&bad_tag, Side::Ask, 10).is_err());
+
+        let mut bad_child = book();
+        inner(&mut bad_child, 2, [3, BOOKSIDE_MAX_NODES as u32]);
+        assert!(walk_book(&bad_child, Side::Bid, 10).is_err());
+    }
+
+    #[test]
+    fn bid_fills_whole_lots_until_input_runs_out() {
+        let asks = [level(5, 3), level(6, 10)];
+        // 3 lots at 50 quote each, then 50 left is short of one 60 lot
+        assert_eq!(quote_take(&market(0), &asks, Side::Bid, 200), 300);
+        assert_eq!(quote_take(&market(0), &asks, Side::Bid, 49), 0);
+        // Taker fee of 20% makes each lot cost 60 and 72
+        assert_eq!(quote_take(&market(200_000), &asks, Side::Bid, 200), 300);
+        assert_eq!(quote_take(&market(200_000), &asks, Side::Bid, 179), 200);
+    }
+
+    #[test]
+    fn ask_sells_base_lots_down_the_book() {
+        let bids = [level(6, 2), level(5, 10)];
+        // 2 lots at 60 quote each then 2 lots at 50, with 50 base left over
+        assert_eq!(quote_take(&market(0), &bids, Side::Ask, 450), 220);
+        // A 0.1% taker fee is taken from each level's proceeds, rounded down
+        assert_eq!(quote_take(&market(1_000), &bids, Side::Ask, 450), 119 + 99);
+    }
+
+    #[test]
+    fn bid_limit_buys_min_out_with_the_whole_budget() {
+        // 1_000 quote lots buy 4 base lots (400 native) at 250 each, or at 200 with a 25% fee
+        let args = take_order_args(&market(0), 10_000, 400, Side::Bid).unwrap();
+        assert_eq!(args.price_lots, 250);
+        assert_eq!(args.max_quote_lots_including_fees, 1_000);
+        assert_eq!(take_order_args(&market(250_000), 10_000, 400, Side::Bid).unwrap().price_lots, 200);
+        // Min-out rounds up to whole base lots
+        assert_eq!(take_order_args(&market(0), 10_000, 301, Side::Bid).unwrap().price_lots, 250);
+        assert_eq!(take_order_args(&market(0), 10_000, 0, Side::Bid).unwrap().price_lots, i64::MAX);
+        assert!(take_order_args(&market(0), 10_000, 1_000_100, Side::Bid).is_err());
+        assert!(take_order_args(&market(0), 9, 0, Side::Bid).is_err());
+    }
+
+    #[test]
+    fn ask_limit_sells_every_lot_for_min_out() {
+        // 5 base lots of 10 quote per price lot: 1_000 quote needs a price of 20, 25 with a 20% fee
+        let args = take_order_args(&market(0), 550, 1_000, Side::Ask).unwrap();
+        assert_eq!(args.price_lots, 20);
+        assert_eq!(args.max_base_lots, 5);
+        assert_eq!(take_order_args(&market(200_000), 550, 1_000, Side::Ask).unwrap().price_lots, 25);
+        // Rounds up so a full fill never pays less than min-out
+        assert_eq!(take_order_args(&market(0), 550, 1_001, Side::Ask).unwrap().price_lots, 21);
+        assert_eq!(take_order_args(&market(0), 550, 0, Side::Ask).unwrap().price_lots, 1);
+        assert!(take_order_args(&market(0), 99, 0, Side::Ask).is_err());
+    }
+
+    #[test]
+    fn quote_skips_empty_levels() {
+        let asks = [level(0, 5), level(5, 0), level(-1, 3), level(5, 3)];
+        assert_eq!(quote_take(&market(0), &asks, Side::Bid, 150), 300);
+        assert_eq!(quote_take(&market(0), &[], Side::Bid, 150), 0);
+    }
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Transfer};
+
+use crate::config::{Config, BPS_DENOMINATOR};
+use crate::venue::load_token_account;
+use crate::error::ErrorCode;
+
+/// `profit * share_bps / 10_000`, rounded down
+pub fn share_of(profit: u64, share_bps: u16) -> u64 {
+    (profit as u128 * share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Wallet and amount of each configured share of `profit`: the operator's first,
+/// then one per beneficiary in config order
+pub fn profit_shares<'a>(
+    config: &'a Config,
+    operator: &Pubkey,
+    profit: u64,
+) -> impl Iterator<Item = (Pubkey, u64)> + 'a {
+    std::iter::once((*operator, config.operator_reward_bps))
+        .chain(
+            config
+                .beneficiaries
+                .iter()
+                .map(|beneficiary| (beneficiary.wallet, beneficiary.share_bps)),
+        )
+        .map(move |(wallet, share_bps)| (wallet, share_of(profit, share_bps)))
+}
+
+/// Pays the configured shares of `profit` out of `vault`, signed by the vault authority.
+/// `recipients` holds the operator's token account followed by one token account per
+/// configured beneficiary, in config order. Returns the total paid out.
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_profit<'info>(
+    config: &Config,
+    operator: &Pubkey,
+    profit: u64,
+    vault: &Account<'info, TokenAccount>,
+    vault_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    recipients: &[AccountInfo<'info>],
+) -> Result<u64> {
+    require_gte!(
+        recipients.len(),
+        1 + config.beneficiaries.len(),
+        ErrorCode::InvalidProfitAccount
+    );
+
+    let mut distributed = 0u64;
+    for ((wallet, amount), recipient) in profit_shares(config, operator, profit).zip(recipients) {
+        if amount == 0 {
+            continue;
+        }
+
+        let destination = load_token_account(recipient)?;
+        require!(
+            destination.mint == vault.mint && destination.owner == wallet,
+            ErrorCode::InvalidProfitAccount
+        );
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: recipient.clone(),
+                    authority: vault_authority.clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        distributed += amount;
+    }
+
+    Ok(distributed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Beneficiary;
+
+    fn config(operator_reward_bps: u16, beneficiaries: &[(Pubkey, u16)]) -> Config {
+        Config {
+            admin: Pubkey::new_unique(),
+            bump: 255,
+            paused: false,
+            vault_authority_bump: 254,
+            lending_program: Pubkey::new_unique(),
+            allowed_reserves: vec![],
+            allowed_pools: vec![],
+            max_borrow_amount: 0,
+            min_profit: 0,
+            treasury: Pubkey::new_unique(),
+            operator_reward_bps,
+            beneficiaries: beneficiaries
+                .iter()
+                .map(|&(wallet, share_bps)| Beneficiary { wallet, share_bps })
+                .collect(),
+            operators: vec![],
+            treasurers: vec![],
+        }
+    }
+
+    #[test]
+    fn share_rounds_down() {
+        assert_eq!(share_of(1_000_000, 2_500), 250_000);
+        assert_eq!(share_of(9_999, 1), 0);
+        assert_eq!(share_of(10_001, 3_333), 3_333);
+        assert_eq!(share_of(123, 0), 0);
+    }
+
+    #[test]
+    fn share_does_not_overflow() {
+        assert_eq!(share_of(u64::MAX, BPS_DENOMINATOR as u16), u64::MAX);
+        assert_eq!(share_of(u64::MAX, 5_000), u64::MAX / 2);
+    }
+
+    #[test]
+    fn shares_go_to_operator_then_beneficiaries() {
+        let operator = Pubkey::new_unique();
+        let team = Pubkey::new_unique();
+        let insurance = Pubkey::new_unique();
+        let config = config(1_000, &[(team, 2_000), (insurance, 500)]);
+
+        let shares: Vec<_> = profit_shares(&config, &operator, 1_000_003).collect();
+        assert_eq!(shares, [(operator, 100_000), (team, 200_000), (insurance, 50_000)]);
+
+        // What is not shared out stays in the vault
+        let paid: u64 = shares.iter().map(|(_, amount)| amount).sum();
+        assert_eq!(1_000_003 - paid, 650_003);
+    }
+
+    #[test]
+    fn operator_share_only() {
+        let operator = Pubkey::new_unique();
+        let shares: Vec<_> = profit_shares(&config(0, &[]), &operator, 500).collect();
+        assert_eq!(shares, [(operator, 0)]);
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::events::{FlashLoanRepaid, ProfitRealized};
+use crate::stats::Strategy;
+use crate::error::ErrorCode;
+
+/// Seed prefix of the receipt PDAs, followed by the operator and the nonce (LE bytes)
+pub const EXECUTION_RECEIPT_SEED: &[u8] = b"execution_receipt";
+
+/// Durable record of one flash-loan run, written when the operator passes a
+/// receipt account and kept until the operator closes it
+#[account]
+#[derive(InitSpace)]
+pub struct ExecutionReceipt {
+    pub operator: Pubkey,
+    /// Operator-chosen nonce the receipt is seeded with
+    pub nonce: u64,
+    pub strategy: Strategy,
+    /// First pool of the run
+    pub pool: Pubkey,
+    /// Mint borrowed, repaid and taken as profit
+    pub mint: Pubkey,
+    pub reserve_liquidity_supply: Pubkey,
+    /// Inputs
+    pub amount: u64,
+    pub flash_loan_fee: u64,
+    pub min_profit: u64,
+    /// Outputs
+    pub amount_out: u64,
+    pub profit: u64,
+    pub distributed: u64,
+    pub slot: u64,
+}
+
+/// Writes `record` into the receipt account when the operator passed one
+pub fn write_receipt(receipt: &mut Option<Box<Account<'_, ExecutionReceipt>>>, record: &ExecutionReceipt) {
+    if let Some(receipt) = receipt.as_mut() {
+        receipt.set_inner(record.clone());
+    }
+}
+
+impl From<&ExecutionReceipt> for FlashLoanRepaid {
+    fn from(record: &ExecutionReceipt) -> Self {
+        Self {
+            reserve_liquidity_supply: record.reserve_liquidity_supply,
+            amount: record.amount,
+            fee: record.flash_loan_fee,
+            slot: record.slot,
+        }
+    }
+}
+
+impl From<&ExecutionReceipt> for ProfitRealized {
+    fn from(record: &ExecutionReceipt) -> Self {
+        Self {
+            operator: record.operator,
+            mint: record.mint,
+            profit: record.profit,
+            distributed: record.distributed,
+            slot: record.slot,
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseExecutionReceipt<'info> {
+    /// Receipt to close once archived, its rent goes back to the operator
+    #[account(mut, close = operator, has_one = operator @ ErrorCode::Unauthorized)]
+    pub receipt: Account<'info, ExecutionReceipt>,
+    /// Operator that paid for the receipt
+    #[account(mut)]
+    pub operator: Signer<'info>,
+}
@@ -0,0 +1,237 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::arbitrage::approve_repayment;
+use crate::config::{Config, CONFIG_SEED};
+use crate::guard::FlashLoan;
+use crate::profit;
+use crate::vault::{self, VAULT_AUTHORITY_SEED};
+use crate::venue::{self, SwapOutcome, Venue};
+use crate::error::ErrorCode;
+
+// Keeps a full route inside the compute and account limits of one transaction
+pub const MAX_ROUTE_STEPS: usize = 4;
+
+/// How much a leg spends
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum AmountMode {
+    /// A fixed native amount
+    Exact(u64),
+    /// Everything the previous leg produced
+    PreviousOutput,
+}
+
+/// One swap leg of a route
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct RouteStep {
+    pub venue: Venue,
+    pub a_to_b: bool,
+    pub amount_mode: AmountMode,
+    pub min_amount_out: u64,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteRoute<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, constraint = !config.paused @ ErrorCode::Paused)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator running the strategy
+    #[account(constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
+    /// CHECK: Program vault authority PDA, owns every leg's user token accounts
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Vault holding the borrowed tokens, where the route starts and ends
+    #[account(mut, token::authority = vault_authority)]
+    pub loan_account: Box<Account<'info, TokenAccount>>,
+
+    /// Reserve the loan was drawn from
+    /// CHECK: Owned by the lender; allowlisted and read for its liquidity supply and fee
+    #[account(owner = config.lending_program @ ErrorCode::ReserveNotAllowed)]
+    pub reserve: AccountInfo<'info>,
+
+    /// Reserve liquidity supply the loan is repaid to, checked against the reserve
+    #[account(token::mint = loan_account.mint)]
+    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Instructions sysvar, read for the lender's borrow and repay around this one
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+/// Result of a whole route, from the first leg's input to the last leg's output
+pub struct RouteOutcome {
+    pub input_account: Pubkey,
+    pub output_account: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// Amounts produced by one routed flash loan
+pub struct RouteLoanOutcome {
+    /// Fee the lender charges on the loan, read from the reserve
+    pub flash_loan_fee: u64,
+    pub amount_out: u64,
+    pub profit: u64,
+    /// Part of the profit paid out to the operator and beneficiaries
+    pub distributed: u64,
+}
+
+impl<'info> ExecuteRoute<'info> {
+    /// Runs `steps` with the borrowed `amount`, sets `amount` plus the reserve's
+    /// flash loan fee aside for the repay and requires at least `min_profit` left over. The route must
+    /// spend from and pay back into the loan vault. `accounts` holds each leg's venue
+    /// accounts, then the profit accounts the profit is split into.
+    pub fn execute(
+        &mut self,
+        amount: u64,
+        steps: &[RouteStep],
+        min_profit: u64,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<RouteLoanOutcome> {
+        let flash_loan_fee = FlashLoan::load(
+            &self.config,
+            &self.instructions,
+            &self.reserve,
+            &self.reserve_liquidity_supply.key(),
+            &self.loan_account.key(),
+            amount,
+        )?
+        .fee;
+        self.config.require_borrow_amount(amount)?;
+        let min_profit = self.config.min_profit(min_profit);
+
+        let token_authority = self.vault_authority.to_account_info();
+        let token_program = self.token_program.to_account_info();
+        let bump = [self.config.vault_authority_bump];
+        let seeds = vault::vault_signer_seeds(&bump);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        // What the vault held before the loan landed in it
+        let balance_before_loan = self
+            .loan_account
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::FlashLoanMismatch)?;
+
+        let (outcome, profit_accounts) = execute_steps(
+            &self.config,
+            steps,
+            accounts,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+        )?;
+        // The route spends the loan out of its vault and brings it back there
+        require_keys_eq!(outcome.input_account, self.loan_account.key(), ErrorCode::InvalidRoute);
+        require_keys_eq!(outcome.output_account, self.loan_account.key(), ErrorCode::InvalidRoute);
+
+        // Proceeds are whatever the vault holds beyond its pre-loan balance
+        self.loan_account.reload()?;
+        let proceeds = self.loan_account.amount.saturating_sub(balance_before_loan);
+
+        let profit = approve_repayment(
+            &token_program,
+            &token_authority,
+            signer_seeds,
+            &self.loan_account,
+            &self.operator.to_account_info(),
+            amount,
+            flash_loan_fee,
+            proceeds,
+            min_profit,
+        )?;
+
+        let distributed = profit::distribute_profit(
+            &self.config,
+            &self.operator.key(),
+            profit,
+            &self.loan_account,
+            &token_authority,
+            &token_program,
+            signer_seeds,
+            profit_accounts,
+        )?;
+
+        Ok(RouteLoanOutcome {
+            flash_loan_fee,
+            amount_out: outcome.amount_out,
+            profit,
+            distributed,
+        })
+    }
+}
+
+/// Runs `steps` in order, slicing each leg's accounts off `accounts` and feeding
+/// each leg's output into the next. Returns the outcome and the unused accounts.
+pub fn execute_steps<'a, 'info>(
+    config: &Config,
+    steps: &[RouteStep],
+    accounts: &'a [AccountInfo<'info>],
+    token_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<(RouteOutcome, &'a [AccountInfo<'info>])> {
+    require!(
+        !steps.is_empty() && steps.len() <= MAX_ROUTE_STEPS,
+        ErrorCode::InvalidRoute
+    );
+
+    let mut remaining = accounts;
+    let mut previous: Option<SwapOutcome> = None;
+    let mut first: Option<SwapOutcome> = None;
+
+    for step in steps {
+        let amount_in = match (step.amount_mode, previous) {
+            (AmountMode::Exact(amount), _) => amount,
+            (AmountMode::PreviousOutput, Some(outcome)) => outcome.amount_out,
+            (AmountMode::PreviousOutput, None) => return Err(ErrorCode::InvalidRoute.into()),
+        };
+
+        let count = venue::account_count(step.venue);
+        require_gte!(remaining.len(), count, ErrorCode::MissingVenueAccounts);
+        let (leg_accounts, rest) = remaining.split_at(count);
+
+        let outcome = venue::execute_venue_swap(
+            config,
+            step.venue,
+            leg_accounts,
+            token_authority,
+            token_program,
+            signer_seeds,
+            amount_in,
+            step.min_amount_out,
+            step.a_to_b,
+        )?;
+
+        // Legs must chain: each one spends what the previous one produced
+        if let Some(prev) = previous {
+            require_keys_eq!(outcome.input_mint, prev.output_mint, ErrorCode::InvalidRoute);
+        }
+
+        first.get_or_insert(outcome);
+        previous = Some(outcome);
+        remaining = rest;
+    }
+
+    let (Some(first), Some(last)) = (first, previous) else {
+        return Err(ErrorCode::InvalidRoute.into());
+    };
+    Ok((
+        RouteOutcome {
+            input_account: first.input_account,
+            output_account: last.output_account,
+            input_mint: first.input_mint,
+            output_mint: last.output_mint,
+            amount_in: first.amount_in,
+            amount_out: last.amount_out,
+        },
+        remaining,
+    ))
+}
@@ -1,48 +1,316 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
 
-// Flash loan instruction discriminator for Solend
-pub const FLASH_LOAN_IX: u8 = 12;
+use crate::config::Config;
+use crate::error::ErrorCode;
 
-// Required by Solend's flash loan instruction
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct FlashLoanParams {
-    pub amount: u64,
+// Solend LendingInstruction tags. The lender rejects both when invoked through CPI,
+// so they run as top-level instructions around the strategy instruction.
+pub const FLASH_BORROW_RESERVE_LIQUIDITY_TAG: u8 = 19;
+pub const FLASH_REPAY_RESERVE_LIQUIDITY_TAG: u8 = 20;
+
+// SPL token-lending Reserve layout, shared by Solend
+const RESERVE_LENDING_MARKET_OFFSET: usize = 10;
+const RESERVE_LIQUIDITY_MINT_OFFSET: usize = 42;
+const RESERVE_LIQUIDITY_SUPPLY_OFFSET: usize = 75;
+const RESERVE_FLASH_LOAN_FEE_WAD_OFFSET: usize = 314;
+const RESERVE_HOST_FEE_PERCENTAGE_OFFSET: usize = 322;
+// Solend appends deposit and borrow limits, then the fee receiver, to the config
+const RESERVE_FEE_RECEIVER_OFFSET: usize = 339;
+const RESERVE_MIN_LEN: usize = RESERVE_FEE_RECEIVER_OFFSET + 32;
+
+// Reserve fees are fractions scaled by 10^18
+const WAD: u128 = 1_000_000_000_000_000_000;
+const HALF_WAD: u128 = WAD / 2;
+
+/// The parts of a lender reserve a flash loan depends on
+#[derive(Clone, Copy, Debug)]
+pub struct ReserveInfo {
+    pub lending_market: Pubkey,
+    pub liquidity_mint: Pubkey,
+    /// Token account loans are drawn from and repaid to
+    pub liquidity_supply: Pubkey,
+    pub flash_loan_fee_wad: u64,
+    pub host_fee_percentage: u8,
+    /// Token account the repay sends the reserve's part of the fee to
+    pub fee_receiver: Pubkey,
 }
 
-pub fn flash_loan_ix(
+impl ReserveInfo {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require_gte!(data.len(), RESERVE_MIN_LEN, ErrorCode::InvalidReserve);
+        let read_pubkey = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+
+        Ok(Self {
+            lending_market: read_pubkey(RESERVE_LENDING_MARKET_OFFSET),
+            liquidity_mint: read_pubkey(RESERVE_LIQUIDITY_MINT_OFFSET),
+            liquidity_supply: read_pubkey(RESERVE_LIQUIDITY_SUPPLY_OFFSET),
+            flash_loan_fee_wad: u64::from_le_bytes(
+                data[RESERVE_FLASH_LOAN_FEE_WAD_OFFSET..RESERVE_FLASH_LOAN_FEE_WAD_OFFSET + 8]
+                    .try_into()
+                    .unwrap(),
+            ),
+            host_fee_percentage: data[RESERVE_HOST_FEE_PERCENTAGE_OFFSET],
+            fee_receiver: read_pubkey(RESERVE_FEE_RECEIVER_OFFSET),
+        })
+    }
+
+    /// Parses an allowlisted `reserve`, whose owner the caller has checked against the
+    /// configured lender, and checks `liquidity_supply` is the one it lends from
+    pub fn load(config: &Config, reserve: &AccountInfo, liquidity_supply: &Pubkey) -> Result<Self> {
+        config.require_reserve(&reserve.key())?;
+        let info = Self::parse(&reserve.try_borrow_data()?)?;
+        require_keys_eq!(info.liquidity_supply, *liquidity_supply, ErrorCode::InvalidReserve);
+        Ok(info)
+    }
+
+    /// Fee on a flash loan of `amount`, computed the way the lender does: the WAD rate
+    /// rounded half up, at least 1 (2 when a host fee is taken) and below the loan itself.
+    /// The host fee is paid out of this, so it is the whole amount owed on top of the loan.
+    pub fn flash_loan_fee(&self, amount: u64) -> Result<u64> {
+        if self.flash_loan_fee_wad == 0 || amount == 0 {
+            return Ok(0);
+        }
+        let minimum_fee = if self.host_fee_percentage > 0 { 2 } else { 1 };
+        let fee = (amount as u128 * self.flash_loan_fee_wad as u128 + HALF_WAD) / WAD;
+        let fee = u64::try_from(fee).map_err(|_| ErrorCode::LenderRejected)?.max(minimum_fee);
+        require_gt!(amount, fee, ErrorCode::LenderRejected);
+        Ok(fee)
+    }
+}
+
+/// Builds Solend's FlashBorrowReserveLiquidity, moving `amount` out of the reserve's
+/// liquidity supply into `destination_liquidity`
+pub fn flash_borrow_reserve_liquidity_ix(
     program_id: Pubkey,
+    amount: u64,
     source_liquidity: Pubkey,
     destination_liquidity: Pubkey,
     reserve: Pubkey,
+    lending_market: Pubkey,
     lending_market_authority: Pubkey,
-    amount: u64,
 ) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new_readonly(program_id, false),
-        AccountMeta::new(source_liquidity, false),
-        AccountMeta::new(destination_liquidity, false),
-        AccountMeta::new(reserve, false),
-        AccountMeta::new_readonly(lending_market_authority, false),
-    ];
+    let mut data = vec![FLASH_BORROW_RESERVE_LIQUIDITY_TAG];
+    data.extend_from_slice(&amount.to_le_bytes());
 
-    let data = FlashLoanParams { amount };
-    let mut encoded = vec![FLASH_LOAN_IX];
-    encoded.extend_from_slice(&data.try_to_vec().unwrap());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(source_liquidity, false),
+            AccountMeta::new(destination_liquidity, false),
+            AccountMeta::new(reserve, false),
+            AccountMeta::new_readonly(lending_market, false),
+            AccountMeta::new_readonly(lending_market_authority, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+        ],
+        data,
+    }
+}
 
-    // Use the constants here
+/// Builds Solend's FlashRepayReserveLiquidity, paying back `amount` plus the fee out of
+/// `source_liquidity`, which `user_transfer_authority` signs for. `borrow_instruction_index`
+/// is the transaction index of the borrow being repaid.
+#[allow(clippy::too_many_arguments)]
+pub fn flash_repay_reserve_liquidity_ix(
+    program_id: Pubkey,
+    amount: u64,
+    borrow_instruction_index: u8,
+    source_liquidity: Pubkey,
+    destination_liquidity: Pubkey,
+    reserve_liquidity_fee_receiver: Pubkey,
+    host_fee_receiver: Pubkey,
+    reserve: Pubkey,
+    lending_market: Pubkey,
+    user_transfer_authority: Pubkey,
+) -> Instruction {
+    let mut data = vec![FLASH_REPAY_RESERVE_LIQUIDITY_TAG];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(borrow_instruction_index);
 
     Instruction {
         program_id,
-        accounts,
-        data: encoded,
+        accounts: vec![
+            AccountMeta::new(source_liquidity, false),
+            AccountMeta::new(destination_liquidity, false),
+            AccountMeta::new(reserve_liquidity_fee_receiver, false),
+            AccountMeta::new(host_fee_receiver, false),
+            AccountMeta::new(reserve, false),
+            AccountMeta::new_readonly(lending_market, false),
+            AccountMeta::new_readonly(user_transfer_authority, true),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+        ],
+        data,
+    }
+}
+
+/// A FlashBorrowReserveLiquidity read back out of the instructions sysvar
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FlashBorrow {
+    pub amount: u64,
+    pub source_liquidity: Pubkey,
+    pub destination_liquidity: Pubkey,
+    pub reserve: Pubkey,
+}
+
+impl FlashBorrow {
+    /// None unless `ix` is a FlashBorrowReserveLiquidity, whatever program it targets
+    pub fn parse(ix: &Instruction) -> Option<Self> {
+        let [FLASH_BORROW_RESERVE_LIQUIDITY_TAG, amount @ ..] = ix.data.as_slice() else {
+            return None;
+        };
+        let [source, destination, reserve, ..] = ix.accounts.as_slice() else {
+            return None;
+        };
+        Some(Self {
+            amount: u64::from_le_bytes(amount.try_into().ok()?),
+            source_liquidity: source.pubkey,
+            destination_liquidity: destination.pubkey,
+            reserve: reserve.pubkey,
+        })
     }
 }
 
-// Constants for Solend's program
-pub mod constants {
-    use anchor_lang::prelude::*;
-    
-    // USDC reserve on mainnet
-    pub static USDC_RESERVE: &str = "BgxfHJDzm44T7XG68MYKx7YisTjZu4NSrPCEZwNdfGH4";
-}
\ No newline at end of file
+/// A FlashRepayReserveLiquidity read back out of the instructions sysvar
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FlashRepay {
+    pub amount: u64,
+    pub borrow_instruction_index: u8,
+    pub source_liquidity: Pubkey,
+    pub destination_liquidity: Pubkey,
+    pub reserve: Pubkey,
+}
+
+impl FlashRepay {
+    /// None unless `ix` is a FlashRepayReserveLiquidity, whatever program it targets
+    pub fn parse(ix: &Instruction) -> Option<Self> {
+        let [FLASH_REPAY_RESERVE_LIQUIDITY_TAG, rest @ ..] = ix.data.as_slice() else {
+            return None;
+        };
+        let (&borrow_instruction_index, amount) = rest.split_last()?;
+        let [source, destination, _fee_receiver, _host_fee_receiver, reserve, ..] = ix.accounts.as_slice() else {
+            return None;
+        };
+        Some(Self {
+            amount: u64::from_le_bytes(amount.try_into().ok()?),
+            borrow_instruction_index,
+            source_liquidity: source.pubkey,
+            destination_liquidity: destination.pubkey,
+            reserve: reserve.pubkey,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::pubkey;
+
+    const SOLEND: Pubkey = pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+
+    #[test]
+    fn flash_borrow_layout() {
+        let [source, destination, reserve, market, authority] = [(); 5].map(|_| Pubkey::new_unique());
+        let ix = flash_borrow_reserve_liquidity_ix(SOLEND, 1_000_000, source, destination, reserve, market, authority);
+
+        assert_eq!(ix.program_id, SOLEND);
+        assert_eq!(ix.data, [19, 0x40, 0x42, 0x0f, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(reserve, false),
+                AccountMeta::new_readonly(market, false),
+                AccountMeta::new_readonly(authority, false),
+                AccountMeta::new_readonly(pubkey!("Sysvar1nstructions1111111111111111111111111"), false),
+                AccountMeta::new_readonly(pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"), false),
+            ]
+        );
+        assert_eq!(
+            FlashBorrow::parse(&ix),
+            Some(FlashBorrow {
+                amount: 1_000_000,
+                source_liquidity: source,
+                destination_liquidity: destination,
+                reserve,
+            })
+        );
+        assert_eq!(FlashRepay::parse(&ix), None);
+    }
+
+    #[test]
+    fn flash_repay_layout() {
+        let [source, destination, fee_receiver, host, reserve, market, authority] =
+            [(); 7].map(|_| Pubkey::new_unique());
+        let ix = flash_repay_reserve_liquidity_ix(
+            SOLEND,
+            u64::MAX - 1,
+            2,
+            source,
+            destination,
+            fee_receiver,
+            host,
+            reserve,
+            market,
+            authority,
+        );
+
+        assert_eq!(ix.data, [20, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 2]);
+        assert_eq!(
+            ix.accounts,
+            [
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(fee_receiver, false),
+                AccountMeta::new(host, false),
+                AccountMeta::new(reserve, false),
+                AccountMeta::new_readonly(market, false),
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new_readonly(pubkey!("Sysvar1nstructions1111111111111111111111111"), false),
+                AccountMeta::new_readonly(pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"), false),
+            ]
+        );
+        assert_eq!(
+            FlashRepay::parse(&ix),
+            Some(FlashRepay {
+                amount: u64::MAX - 1,
+                borrow_instruction_index: 2,
+                source_liquidity: source,
+                destination_liquidity: destination,
+                reserve,
+            })
+        );
+        assert_eq!(FlashBorrow::parse(&ix), None);
+    }
+
+    #[test]
+    fn rejects_truncated_instructions() {
+        let mut ix = flash_borrow_reserve_liquidity_ix(SOLEND, 5, SOLEND, SOLEND, SOLEND, SOLEND, SOLEND);
+        ix.data.pop();
+        assert_eq!(FlashBorrow::parse(&ix), None);
+
+        let mut ix = flash_repay_reserve_liquidity_ix(SOLEND, 5, 0, SOLEND, SOLEND, SOLEND, SOLEND, SOLEND, SOLEND, SOLEND);
+        ix.accounts.truncate(4);
+        assert_eq!(FlashRepay::parse(&ix), None);
+    }
+
+    #[test]
+    fn reads_the_reserve_fee_receiver() {
+        let mut data = vec![0; RESERVE_MIN_LEN];
+        let fee_receiver = Pubkey::new_unique();
+        data[RESERVE_FEE_RECEIVER_OFFSET..].copy_from_slice(fee_receiver.as_ref());
+        data[RESERVE_FLASH_LOAN_FEE_WAD_OFFSET..RESERVE_FLASH_LOAN_FEE_WAD_OFFSET + 8]
+            .copy_from_slice(&3_000_000_000_000_000u64.to_le_bytes());
+        data[RESERVE_HOST_FEE_PERCENTAGE_OFFSET] = 20;
+
+        let info = ReserveInfo::parse(&data).unwrap();
+        assert_eq!(info.fee_receiver, fee_receiver);
+        // 0.3% of 1 USDC, then the two-unit floor once a host fee is taken
+        assert_eq!(info.flash_loan_fee(1_000_000).unwrap(), 3_000);
+        assert_eq!(info.flash_loan_fee(10).unwrap(), 2);
+        assert!(ReserveInfo::parse(&data[..RESERVE_MIN_LEN - 1]).is_err());
+    }
+}
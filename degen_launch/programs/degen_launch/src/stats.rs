@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::config::{Config, CONFIG_SEED};
+use crate::error::ErrorCode;
+
+/// Seed prefix of the stats PDAs, followed by the strategy seed and the pool
+pub const STRATEGY_STATS_SEED: &[u8] = b"strategy_stats";
+
+/// Strategies that keep on-chain statistics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum Strategy {
+    /// execute_flashloan_selfdump, keyed by its whirlpool
+    Selfdump,
+    /// execute_round_trip_arbitrage, keyed by the pool DEGEN is bought on
+    RoundTrip,
+    /// execute_triangular_arbitrage, keyed by the first pool of the cycle
+    Triangular,
+}
+
+impl Strategy {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            Strategy::Selfdump => b"selfdump",
+            Strategy::RoundTrip => b"round_trip",
+            Strategy::Triangular => b"triangular",
+        }
+    }
+}
+
+/// Why an off-chain attempt failed. A failing strategy transaction reverts every
+/// write it made, so operators report failures through record_failure.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FailureKind {
+    /// A swap returned less than its minimum
+    Slippage,
+    /// The run could not repay the loan plus the profit floor
+    InsufficientProfit,
+    /// Anything else, e.g. a lender or venue error
+    Other,
+}
+
+/// Running totals for one strategy on one pool, at
+/// [STRATEGY_STATS_SEED, strategy seed, pool]
+#[account]
+#[derive(InitSpace)]
+pub struct StrategyStats {
+    pub strategy: Strategy,
+    pub pool: Pubkey,
+    pub bump: u8,
+    /// Successful runs
+    pub run_count: u64,
+    /// Sum of every flash loan borrowed by those runs
+    pub total_borrowed: u128,
+    /// Sum of every flash loan fee paid
+    pub total_fees: u128,
+    /// Sum of every profit realised, before it is split
+    pub total_profit: u128,
+    pub last_success_slot: u64,
+    pub slippage_failures: u64,
+    pub insufficient_profit_failures: u64,
+    pub other_failures: u64,
+    pub last_failure_slot: u64,
+}
+
+impl StrategyStats {
+    pub fn record_success(&mut self, borrowed: u64, fee: u64, profit: u64) -> Result<()> {
+        self.run_count = self.run_count.saturating_add(1);
+        self.total_borrowed = self.total_borrowed.saturating_add(borrowed as u128);
+        self.total_fees = self.total_fees.saturating_add(fee as u128);
+        self.total_profit = self.total_profit.saturating_add(profit as u128);
+        self.last_success_slot = Clock::get()?.slot;
+        Ok(())
+    }
+
+    pub fn record_failure(&mut self, kind: FailureKind) -> Result<()> {
+        let counter = match kind {
+            FailureKind::Slippage => &mut self.slippage_failures,
+            FailureKind::InsufficientProfit => &mut self.insufficient_profit_failures,
+            FailureKind::Other => &mut self.other_failures,
+        };
+        *counter = counter.saturating_add(1);
+        self.last_failure_slot = Clock::get()?.slot;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(strategy: Strategy, pool: Pubkey)]
+pub struct InitializeStrategyStats<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + StrategyStats::INIT_SPACE,
+        seeds = [STRATEGY_STATS_SEED, strategy.seed(), pool.as_ref()],
+        bump,
+    )]
+    pub stats: Account<'info, StrategyStats>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeStrategyStats<'info> {
+    pub fn initialize(&mut self, strategy: Strategy, pool: Pubkey, bump: u8) -> Result<()> {
+        self.config.require_pool(&pool)?;
+        self.stats.strategy = strategy;
+        self.stats.pool = pool;
+        self.stats.bump = bump;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RecordFailure<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator reporting its own failed attempt
+    #[account(constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [STRATEGY_STATS_SEED, stats.strategy.seed(), stats.pool.as_ref()],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, StrategyStats>,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::config::{Config, CONFIG_SEED};
+use crate::events::Withdrawn;
+use crate::vault::{self, VaultLedger, VAULT_AUTHORITY_SEED, VAULT_LEDGER_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TreasuryTransfer<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Holder of the treasurer role
+    #[account(constraint = config.is_treasurer(&treasurer.key()) @ ErrorCode::Unauthorized)]
+    pub treasurer: Signer<'info>,
+    /// CHECK: Program vault authority PDA, signs the transfer
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [VAULT_LEDGER_SEED, ledger.mint.as_ref()], bump = ledger.bump)]
+    pub ledger: Account<'info, VaultLedger>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, ledger.mint.as_ref()],
+        bump = ledger.vault_bump,
+        token::authority = vault_authority,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    /// Treasury token account for the vault's mint
+    #[account(
+        mut,
+        token::mint = ledger.mint,
+        constraint = treasury_token_account.owner == config.treasury @ ErrorCode::Unauthorized,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> TreasuryTransfer<'info> {
+    /// Moves everything above the minimum working balance to the treasury
+    pub fn sweep_profits(&mut self) -> Result<Withdrawn> {
+        let amount = self
+            .vault
+            .amount
+            .saturating_sub(self.ledger.min_working_balance);
+        self.withdraw(amount)
+    }
+
+    /// Moves `amount` to the treasury as long as the minimum working balance stays behind.
+    /// Returns the event recording the move.
+    pub fn withdraw(&mut self, amount: u64) -> Result<Withdrawn> {
+        let remaining = self
+            .vault
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::BelowMinWorkingBalance)?;
+        require_gte!(
+            remaining,
+            self.ledger.min_working_balance,
+            ErrorCode::BelowMinWorkingBalance
+        );
+
+        let bump = [self.config.vault_authority_bump];
+        let seeds = vault::vault_signer_seeds(&bump);
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.treasury_token_account.to_account_info(),
+                    authority: self.vault_authority.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            amount,
+        )?;
+
+        let slot = Clock::get()?.slot;
+        let ledger = &mut self.ledger;
+        ledger.total_withdrawn = ledger.total_withdrawn.saturating_add(amount);
+        ledger.last_withdrawn_by = self.treasurer.key();
+        ledger.last_withdrawn_amount = amount;
+        ledger.last_withdrawn_slot = slot;
+
+        Ok(Withdrawn {
+            treasurer: self.treasurer.key(),
+            mint: ledger.mint,
+            amount,
+            slot,
+        })
+    }
+}
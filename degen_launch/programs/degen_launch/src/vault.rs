@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::config::{Config, CONFIG_SEED};
+use crate::error::ErrorCode;
+
+/// Seed of the PDA that owns every program vault and signs for them
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+/// Seed prefix of the per-mint vault token accounts, followed by the mint
+pub const VAULT_SEED: &[u8] = b"vault";
+/// Seed prefix of the per-mint vault ledgers, followed by the mint
+pub const VAULT_LEDGER_SEED: &[u8] = b"vault_ledger";
+
+/// Per-mint vault settings and the record of the last treasury move
+#[account]
+#[derive(InitSpace)]
+pub struct VaultLedger {
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub vault_bump: u8,
+    /// Balance treasury moves must leave behind as working capital
+    pub min_working_balance: u64,
+    /// Sum of every amount moved to the treasury
+    pub total_withdrawn: u64,
+    /// Latest move only; every move also emits a Withdrawn event
+    pub last_withdrawn_by: Pubkey,
+    pub last_withdrawn_amount: u64,
+    pub last_withdrawn_slot: u64,
+}
+
+/// Signer seeds for the vault authority, given its bump stored in the config
+pub fn vault_signer_seeds(bump: &[u8; 1]) -> [&[u8]; 2] {
+    [VAULT_AUTHORITY_SEED, bump]
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: PDA that will own the vault, holds no data
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    /// Program-owned working capital account for `mint`
+    #[account(
+        init,
+        payer = admin,
+        seeds = [VAULT_SEED, mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VaultLedger::INIT_SPACE,
+        seeds = [VAULT_LEDGER_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub ledger: Account<'info, VaultLedger>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> InitializeVault<'info> {
+    pub fn initialize(&mut self, bumps: &InitializeVaultBumps, min_working_balance: u64) -> Result<()> {
+        self.ledger.mint = self.mint.key();
+        self.ledger.bump = bumps.ledger;
+        self.ledger.vault_bump = bumps.vault;
+        self.ledger.min_working_balance = min_working_balance;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetMinWorkingBalance<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Box<Account<'info, Config>>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [VAULT_LEDGER_SEED, ledger.mint.as_ref()], bump = ledger.bump)]
+    pub ledger: Account<'info, VaultLedger>,
+}
+
+impl<'info> SetMinWorkingBalance<'info> {
+    pub fn set(&mut self, min_working_balance: u64) -> Result<()> {
+        self.ledger.min_working_balance = min_working_balance;
+        Ok(())
+    }
+}
@@ -0,0 +1,459 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::config::{Config, CONFIG_SEED};
+use crate::openbook::{self, MarketInfo, Side, TakeOrderKeys};
+use crate::vault::VAULT_AUTHORITY_SEED;
+use crate::error::ErrorCode;
+use crate::whirlpool_utils;
+
+/// Venue tag passed by the client to select how the next accounts are interpreted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum Venue {
+    Orca,
+    OpenbookV2,
+}
+
+/// Result of one executed swap leg
+#[derive(Clone, Copy)]
+pub struct SwapOutcome {
+    pub pool: Pubkey,
+    pub input_account: Pubkey,
+    pub output_account: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// A swap venue whose accounts arrive as a fixed-size slice of remaining_accounts.
+/// `a_to_b` means token A in, token B out; for order books A is base and B is quote.
+pub trait SwapVenue<'info>: Sized {
+    /// Number of remaining accounts the venue consumes
+    const ACCOUNT_COUNT: usize;
+
+    /// Builds the venue from its account slice and validates it
+    fn load(accounts: &[AccountInfo<'info>]) -> Result<Self>;
+
+    /// Pool or market address, checked against the config allowlist
+    fn pool(&self) -> Pubkey;
+
+    /// Input and output mints for the given direction
+    fn mints(&self, a_to_b: bool) -> (Pubkey, Pubkey);
+
+    /// Token account that pays the input for the given direction
+    fn input_account(&self, a_to_b: bool) -> &AccountInfo<'info>;
+
+    /// Token account that receives the output for the given direction
+    fn output_account(&self, a_to_b: bool) -> &AccountInfo<'info>;
+
+    /// Invokes the venue program, signing for `token_authority` with `signer_seeds`
+    fn invoke_swap(
+        &self,
+        token_authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        amount_in: u64,
+        minimum_amount_out: u64,
+        a_to_b: bool,
+    ) -> Result<()>;
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SwapViaVenue<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, constraint = !config.paused @ ErrorCode::Paused)]
+    pub config: Box<Account<'info, Config>>,
+    /// Allowlisted operator running the strategy
+    #[account(constraint = config.is_operator(&operator.key()) @ ErrorCode::Unauthorized)]
+    pub operator: Signer<'info>,
+    /// CHECK: Program vault authority PDA, owns the venue's user token accounts
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump = config.vault_authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Number of remaining accounts a venue consumes
+pub fn account_count(venue: Venue) -> usize {
+    match venue {
+        Venue::Orca => OrcaVenue::ACCOUNT_COUNT,
+        Venue::OpenbookV2 => OpenbookVenue::ACCOUNT_COUNT,
+    }
+}
+
+/// Runs one swap leg on `venue` using the leading accounts of `accounts`
+#[allow(clippy::too_many_arguments)]
+pub fn execute_venue_swap<'info>(
+    config: &Config,
+    venue: Venue,
+    accounts: &[AccountInfo<'info>],
+    token_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount_in: u64,
+    minimum_amount_out: u64,
+    a_to_b: bool,
+) -> Result<SwapOutcome> {
+    match venue {
+        Venue::Orca => execute::<OrcaVenue>(
+            config,
+            accounts,
+            token_authority,
+            token_program,
+            signer_seeds,
+            amount_in,
+            minimum_amount_out,
+            a_to_b,
+        ),
+        Venue::OpenbookV2 => execute::<OpenbookVenue>(
+            config,
+            accounts,
+            token_authority,
+            token_program,
+            signer_seeds,
+            amount_in,
+            minimum_amount_out,
+            a_to_b,
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute<'info, V: SwapVenue<'info>>(
+    config: &Config,
+    accounts: &[AccountInfo<'info>],
+    token_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount_in: u64,
+    minimum_amount_out: u64,
+    a_to_b: bool,
+) -> Result<SwapOutcome> {
+    require_gte!(accounts.len(), V::ACCOUNT_COUNT, ErrorCode::MissingVenueAccounts);
+    // A leg fed by a previous one that produced nothing has nothing to trade
+    require_gt!(amount_in, 0, ErrorCode::SwapAmountTooSmall);
+    let venue = V::load(&accounts[..V::ACCOUNT_COUNT])?;
+    config.require_pool(&venue.pool())?;
+
+    let (input_mint, output_mint) = venue.mints(a_to_b);
+    // The output must stay with the vault authority, which signs for every leg
+    let output = load_token_account(venue.output_account(a_to_b))?;
+    require_keys_eq!(output.owner, token_authority.key(), ErrorCode::VenueAccountMismatch);
+    let balance_before = output.amount;
+
+    venue.invoke_swap(
+        token_authority,
+        token_program,
+        signer_seeds,
+        amount_in,
+        minimum_amount_out,
+        a_to_b,
+    )?;
+
+    // Min-out is enforced on what actually landed, whatever the venue reports
+    let amount_out = load_token_account(venue.output_account(a_to_b))?
+        .amount
+        .saturating_sub(balance_before);
+    require_gte!(amount_out, minimum_amount_out, ErrorCode::SlippageExceeded);
+
+    Ok(SwapOutcome {
+        pool: venue.pool(),
+        input_account: venue.input_account(a_to_b).key(),
+        output_account: venue.output_account(a_to_b).key(),
+        input_mint,
+        output_mint,
+        amount_in,
+        amount_out,
+    })
+}
+
+pub fn load_token_account(account: &AccountInfo) -> Result<TokenAccount> {
+    require_keys_eq!(*account.owner, token::ID, ErrorCode::VenueAccountMismatch);
+    TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])
+}
+
+/// Orca Whirlpool swap. Accounts, in order:
+/// whirlpool_program, whirlpool, token_owner_account_a, token_vault_a,
+/// token_owner_account_b, token_vault_b, tick_array_0, tick_array_1,
+/// tick_array_2, oracle
+pub struct OrcaVenue<'info> {
+    whirlpool_program: AccountInfo<'info>,
+    whirlpool: AccountInfo<'info>,
+    token_owner_account_a: AccountInfo<'info>,
+    token_vault_a: AccountInfo<'info>,
+    token_owner_account_b: AccountInfo<'info>,
+    token_vault_b: AccountInfo<'info>,
+    tick_array_0: AccountInfo<'info>,
+    tick_array_1: AccountInfo<'info>,
+    tick_array_2: AccountInfo<'info>,
+    oracle: AccountInfo<'info>,
+    token_mint_a: Pubkey,
+    token_mint_b: Pubkey,
+}
+
+impl<'info> SwapVenue<'info> for OrcaVenue<'info> {
+    const ACCOUNT_COUNT: usize = 10;
+
+    fn load(accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let [whirlpool_program, whirlpool, token_owner_account_a, token_vault_a, token_owner_account_b, token_vault_b, tick_array_0, tick_array_1, tick_array_2, oracle] =
+            accounts
+        else {
+            return Err(ErrorCode::MissingVenueAccounts.into());
+        };
+
+        require_keys_eq!(whirlpool_program.key(), whirlpool_cpi::ID, ErrorCode::InvalidWhirlpoolAccount);
+        require_keys_eq!(*whirlpool.owner, whirlpool_cpi::ID, ErrorCode::InvalidWhirlpoolAccount);
+        for tick_array in [tick_array_0, tick_array_1, tick_array_2] {
+            require_keys_eq!(*tick_array.owner, whirlpool_cpi::ID, ErrorCode::InvalidWhirlpoolAccount);
+        }
+
+        let tokens = whirlpool_utils::parse_tokens_from_whirlpool(&whirlpool.try_borrow_data()?)?;
+        require_keys_eq!(token_vault_a.key(), tokens.token_vault_a, ErrorCode::VenueAccountMismatch);
+        require_keys_eq!(token_vault_b.key(), tokens.token_vault_b, ErrorCode::VenueAccountMismatch);
+        require_keys_eq!(
+            load_token_account(token_owner_account_a)?.mint,
+            tokens.token_mint_a,
+            ErrorCode::VenueAccountMismatch
+        );
+        require_keys_eq!(
+            load_token_account(token_owner_account_b)?.mint,
+            tokens.token_mint_b,
+            ErrorCode::VenueAccountMismatch
+        );
+
+        Ok(Self {
+            whirlpool_program: whirlpool_program.clone(),
+            whirlpool: whirlpool.clone(),
+            token_owner_account_a: token_owner_account_a.clone(),
+            token_vault_a: token_vault_a.clone(),
+            token_owner_account_b: token_owner_account_b.clone(),
+            token_vault_b: token_vault_b.clone(),
+            tick_array_0: tick_array_0.clone(),
+            tick_array_1: tick_array_1.clone(),
+            tick_array_2: tick_array_2.clone(),
+            oracle: oracle.clone(),
+            token_mint_a: tokens.token_mint_a,
+            token_mint_b: tokens.token_mint_b,
+        })
+    }
+
+    fn pool(&self) -> Pubkey {
+        self.whirlpool.key()
+    }
+
+    fn mints(&self, a_to_b: bool) -> (Pubkey, Pubkey) {
+        if a_to_b {
+            (self.token_mint_a, self.token_mint_b)
+        } else {
+            (self.token_mint_b, self.token_mint_a)
+        }
+    }
+
+    fn input_account(&self, a_to_b: bool) -> &AccountInfo<'info> {
+        if a_to_b {
+            &self.token_owner_account_a
+        } else {
+            &self.token_owner_account_b
+        }
+    }
+
+    fn output_account(&self, a_to_b: bool) -> &AccountInfo<'info> {
+        if a_to_b {
+            &self.token_owner_account_b
+        } else {
+            &self.token_owner_account_a
+        }
+    }
+
+    fn invoke_swap(
+        &self,
+        token_authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        amount_in: u64,
+        minimum_amount_out: u64,
+        a_to_b: bool,
+    ) -> Result<()> {
+        let cpi_accounts = whirlpool_cpi::cpi::accounts::Swap {
+            whirlpool: self.whirlpool.clone(),
+            token_program: token_program.clone(),
+            token_authority: token_authority.clone(),
+            token_owner_account_a: self.token_owner_account_a.clone(),
+            token_vault_a: self.token_vault_a.clone(),
+            token_owner_account_b: self.token_owner_account_b.clone(),
+            token_vault_b: self.token_vault_b.clone(),
+            tick_array_0: self.tick_array_0.clone(),
+            tick_array_1: self.tick_array_1.clone(),
+            tick_array_2: self.tick_array_2.clone(),
+            oracle: self.oracle.clone(),
+        };
+
+        whirlpool_cpi::cpi::swap(
+            CpiContext::new_with_signer(self.whirlpool_program.clone(), cpi_accounts, signer_seeds),
+            amount_in,
+            minimum_amount_out,
+            whirlpool_utils::sqrt_price_limit(a_to_b),
+            true, // amount_specified_is_input
+            a_to_b,
+        )
+    }
+}
+
+/// OpenBook v2 taker order. Accounts, in order:
+/// openbook_program, market, market_authority, bids, asks, event_heap,
+/// market_base_vault, market_quote_vault, user_base_account,
+/// user_quote_account, oracle_a, oracle_b, system_program, penalty_payer
+pub struct OpenbookVenue<'info> {
+    openbook_program: AccountInfo<'info>,
+    penalty_payer: AccountInfo<'info>,
+    market: AccountInfo<'info>,
+    market_authority: AccountInfo<'info>,
+    bids: AccountInfo<'info>,
+    asks: AccountInfo<'info>,
+    event_heap: AccountInfo<'info>,
+    market_base_vault: AccountInfo<'info>,
+    market_quote_vault: AccountInfo<'info>,
+    user_base_account: AccountInfo<'info>,
+    user_quote_account: AccountInfo<'info>,
+    oracle_a: AccountInfo<'info>,
+    oracle_b: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    market_info: MarketInfo,
+}
+
+impl<'info> SwapVenue<'info> for OpenbookVenue<'info> {
+    const ACCOUNT_COUNT: usize = 14;
+
+    fn load(accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let [openbook_program, market, market_authority, bids, asks, event_heap, market_base_vault, market_quote_vault, user_base_account, user_quote_account, oracle_a, oracle_b, system_program, penalty_payer] =
+            accounts
+        else {
+            return Err(ErrorCode::MissingVenueAccounts.into());
+        };
+
+        let market_info = MarketInfo::load(openbook_program, market, bids, asks, event_heap)?;
+
+        // The vault authority PDA cannot pay lamport penalties, the operator does
+        require!(penalty_payer.is_signer, ErrorCode::Unauthorized);
+
+        require_keys_eq!(market_base_vault.key(), market_info.base_vault, ErrorCode::VenueAccountMismatch);
+        require_keys_eq!(market_quote_vault.key(), market_info.quote_vault, ErrorCode::VenueAccountMismatch);
+        require_keys_eq!(
+            load_token_account(user_base_account)?.mint,
+            market_info.base_mint,
+            ErrorCode::VenueAccountMismatch
+        );
+        require_keys_eq!(
+            load_token_account(user_quote_account)?.mint,
+            market_info.quote_mint,
+            ErrorCode::VenueAccountMismatch
+        );
+
+        Ok(Self {
+            openbook_program: openbook_program.clone(),
+            penalty_payer: penalty_payer.clone(),
+            market: market.clone(),
+            market_authority: market_authority.clone(),
+            bids: bids.clone(),
+            asks: asks.clone(),
+            event_heap: event_heap.clone(),
+            market_base_vault: market_base_vault.clone(),
+            market_quote_vault: market_quote_vault.clone(),
+            user_base_account: user_base_account.clone(),
+            user_quote_account: user_quote_account.clone(),
+            oracle_a: oracle_a.clone(),
+            oracle_b: oracle_b.clone(),
+            system_program: system_program.clone(),
+            market_info,
+        })
+    }
+
+    fn pool(&self) -> Pubkey {
+        self.market.key()
+    }
+
+    fn mints(&self, a_to_b: bool) -> (Pubkey, Pubkey) {
+        let market = &self.market_info;
+        if a_to_b {
+            (market.base_mint, market.quote_mint)
+        } else {
+            (market.quote_mint, market.base_mint)
+        }
+    }
+
+    fn input_account(&self, a_to_b: bool) -> &AccountInfo<'info> {
+        if a_to_b {
+            &self.user_base_account
+        } else {
+            &self.user_quote_account
+        }
+    }
+
+    fn output_account(&self, a_to_b: bool) -> &AccountInfo<'info> {
+        if a_to_b {
+            &self.user_quote_account
+        } else {
+            &self.user_base_account
+        }
+    }
+
+    fn invoke_swap(
+        &self,
+        token_authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        amount_in: u64,
+        minimum_amount_out: u64,
+        a_to_b: bool,
+    ) -> Result<()> {
+        // Selling base (A) for quote (B) is an ask
+        let side = if a_to_b { Side::Ask } else { Side::Bid };
+
+        let keys = TakeOrderKeys {
+            program_id: self.openbook_program.key(),
+            signer: token_authority.key(),
+            penalty_payer: self.penalty_payer.key(),
+            market: self.market.key(),
+            market_authority: self.market_authority.key(),
+            bids: self.bids.key(),
+            asks: self.asks.key(),
+            market_base_vault: self.market_base_vault.key(),
+            market_quote_vault: self.market_quote_vault.key(),
+            event_heap: self.event_heap.key(),
+            user_base_account: self.user_base_account.key(),
+            user_quote_account: self.user_quote_account.key(),
+            oracle_a: self.oracle_a.key(),
+            oracle_b: self.oracle_b.key(),
+            token_program: token_program.key(),
+            system_program: self.system_program.key(),
+        };
+        let args = openbook::take_order_args(&self.market_info, amount_in, minimum_amount_out, side)?;
+
+        invoke_signed(
+            &openbook::place_take_order_ix(&keys, &args)?,
+            &[
+                self.openbook_program.clone(),
+                token_authority.clone(),
+                self.penalty_payer.clone(),
+                self.market.clone(),
+                self.market_authority.clone(),
+                self.bids.clone(),
+                self.asks.clone(),
+                self.market_base_vault.clone(),
+                self.market_quote_vault.clone(),
+                self.event_heap.clone(),
+                self.user_base_account.clone(),
+                self.user_quote_account.clone(),
+                self.oracle_a.clone(),
+                self.oracle_b.clone(),
+                token_program.clone(),
+                self.system_program.clone(),
+            ],
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,148 +1,163 @@
 use anchor_lang::prelude::*;
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid Whirlpool account data.")]
-    InvalidWhirlpoolAccount,
-}
+use crate::error::ErrorCode;
 
 pub fn parse_tick_index_from_whirlpool(data: &[u8]) -> Result<i32> {
-    if data.len() < 77 {
+    if data.len() < 85 {
         return Err(ErrorCode::InvalidWhirlpoolAccount.into());
     }
 
-    // tick_current_index starts at offset 73 and is 4 bytes long
-    let tick_bytes = &data[73..77];
+    // tick_current_index starts at offset 81 (after the discriminator) and is 4 bytes long
+    let tick_bytes = &data[81..85];
     let tick_array: [u8; 4] = tick_bytes.try_into().unwrap();
     Ok(i32::from_le_bytes(tick_array))
 }
 
-// Keep the TICK_ARRAY_SIZE constant
-const TICK_ARRAY_SIZE: i32 = 88;
+pub fn parse_tick_spacing_from_whirlpool(data: &[u8]) -> Result<u16> {
+    if data.len() < 43 {
+        return Err(ErrorCode::InvalidWhirlpoolAccount.into());
+    }
 
-// Keep the existing derive_tick_arrays function
-pub fn derive_tick_arrays(
-    whirlpool: &Pubkey,
-    whirlpool_program: &Pubkey,
-    current_tick_index: i32,
-) -> (Pubkey, Pubkey, Pubkey) {
-    // Calculate base tick index
-    let start_tick_index = (current_tick_index / TICK_ARRAY_SIZE) * TICK_ARRAY_SIZE;
-    
-    // Derive tick_array_0 (current)
-    let (tick_array_0, _) = Pubkey::find_program_address(
-        &[
-            b"tick_array",
-            whirlpool.as_ref(),
-            &start_tick_index.to_le_bytes(),
-        ],
-        whirlpool_program,
-    );
-
-    // Derive tick_array_1 (next)
-    let (tick_array_1, _) = Pubkey::find_program_address(
-        &[
-            b"tick_array",
-            whirlpool.as_ref(),
-            &(start_tick_index + TICK_ARRAY_SIZE).to_le_bytes(),
-        ],
-        whirlpool_program,
-    );
-
-    // Derive tick_array_2 (previous)
-    let (tick_array_2, _) = Pubkey::find_program_address(
-        &[
-            b"tick_array",
-            whirlpool.as_ref(),
-            &(start_tick_index - TICK_ARRAY_SIZE).to_le_bytes(),
-        ],
-        whirlpool_program,
-    );
+    // tick_spacing starts at offset 41 and is 2 bytes long
+    Ok(u16::from_le_bytes(data[41..43].try_into().unwrap()))
+}
+
+// Whirlpool account layout (offsets include the 8 byte discriminator)
+const WHIRLPOOLS_CONFIG_OFFSET: usize = 8;
+const TOKEN_MINT_A_OFFSET: usize = 101;
+const TOKEN_VAULT_A_OFFSET: usize = 133;
+const TOKEN_MINT_B_OFFSET: usize = 181;
+const TOKEN_VAULT_B_OFFSET: usize = 213;
+
+// Price limits accepted by Whirlpool when a swap should not be capped
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+/// Mints and vaults of a whirlpool, read straight from its account data
+pub struct WhirlpoolTokens {
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
 
-    (tick_array_0, tick_array_1, tick_array_2)
+pub fn parse_whirlpools_config(data: &[u8]) -> Result<Pubkey> {
+    if data.len() < WHIRLPOOLS_CONFIG_OFFSET + 32 {
+        return Err(ErrorCode::InvalidWhirlpoolAccount.into());
+    }
+    Ok(Pubkey::new_from_array(
+        data[WHIRLPOOLS_CONFIG_OFFSET..WHIRLPOOLS_CONFIG_OFFSET + 32].try_into().unwrap(),
+    ))
 }
 
-pub struct WhirlpoolPdas {
-    pub whirlpool: Pubkey,
-    pub oracle: Pubkey,
-    pub tick_array_0: Pubkey,
-    pub tick_array_1: Option<Pubkey>,
-    pub tick_array_2: Option<Pubkey>,
-    pub fee_tier: Pubkey,
+pub fn parse_tokens_from_whirlpool(data: &[u8]) -> Result<WhirlpoolTokens> {
+    if data.len() < TOKEN_VAULT_B_OFFSET + 32 {
+        return Err(ErrorCode::InvalidWhirlpoolAccount.into());
+    }
+
+    let read = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+    Ok(WhirlpoolTokens {
+        token_mint_a: read(TOKEN_MINT_A_OFFSET),
+        token_vault_a: read(TOKEN_VAULT_A_OFFSET),
+        token_mint_b: read(TOKEN_MINT_B_OFFSET),
+        token_vault_b: read(TOKEN_VAULT_B_OFFSET),
+    })
 }
 
-impl WhirlpoolPdas {
-    pub fn new(
-        whirlpool_account_data: &[u8],
-        whirlpool: Pubkey,
-        whirlpool_program: Pubkey,
-        fee_tier_value: u16, // Add this parameter
-    ) -> Result<Self> {
-        let current_tick_index = parse_tick_index_from_whirlpool(whirlpool_account_data)?;
-
-        let (oracle, _) = Pubkey::find_program_address(
-            &[b"oracle", whirlpool.as_ref()],
-            &whirlpool_program,
-        );
-
-        let (tick_array_0, tick_array_1, tick_array_2) =
-            derive_tick_arrays(&whirlpool, &whirlpool_program, current_tick_index);
-
-        let (fee_tier, _) = Pubkey::find_program_address(
-            &[b"fee_tier", &fee_tier_value.to_le_bytes()],
-            &whirlpool_program,
-        );
-
-        Ok(Self {
-            whirlpool,
-            oracle,
-            tick_array_0,
-            tick_array_1: Some(tick_array_1),
-            tick_array_2: Some(tick_array_2),
-            fee_tier,
-        })
+/// Uncapped sqrt price limit for a swap in the given direction
+pub fn sqrt_price_limit(a_to_b: bool) -> u128 {
+    if a_to_b {
+        MIN_SQRT_PRICE_X64
+    } else {
+        MAX_SQRT_PRICE_X64
     }
 }
 
-// Update derive_whirlpool_pdas to pass the fee_tier value
-pub fn derive_whirlpool_pdas(
+// Keep the TICK_ARRAY_SIZE constant
+const TICK_ARRAY_SIZE: i32 = 88;
+
+/// Start indexes of the three tick arrays a swap in the given direction walks through,
+/// starting with the array holding the current tick
+pub fn tick_array_start_indexes(current_tick_index: i32, tick_spacing: u16, a_to_b: bool) -> [i32; 3] {
+    // Each tick array covers TICK_ARRAY_SIZE initializable ticks
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    let start_tick_index = current_tick_index.div_euclid(ticks_in_array) * ticks_in_array;
+
+    // a_to_b moves the price down, b_to_a moves it up
+    let step = if a_to_b { -ticks_in_array } else { ticks_in_array };
+
+    [start_tick_index, start_tick_index + step, start_tick_index + 2 * step]
+}
+
+/// Bumps of a whirlpool's oracle and of the tick arrays a swap walks, found
+/// off-chain so the program only has to check them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub struct WhirlpoolBumps {
+    pub oracle: u8,
+    pub tick_arrays: [u8; 3],
+}
+
+/// Checks the oracle and tick arrays passed for a swap against the whirlpool's
+/// current tick with create_program_address, which is far cheaper than searching
+/// for the bumps. Tick arrays passed as None are not checked.
+pub fn verify_swap_pdas(
     whirlpool_program: &Pubkey,
-    token_mint_a: &Pubkey,
-    token_mint_b: &Pubkey,
-    fee_tier: u16,
-    whirlpool_data: Option<&[u8]>,
-) -> Result<WhirlpoolPdas> {
-    let (whirlpool, _) = Pubkey::find_program_address(
-        &[
-            b"whirlpool",
-            token_mint_a.as_ref(),
-            token_mint_b.as_ref(),
-            &fee_tier.to_le_bytes(),
-        ],
+    whirlpool: &Pubkey,
+    whirlpool_account_data: &[u8],
+    a_to_b: bool,
+    bumps: &WhirlpoolBumps,
+    oracle: &Pubkey,
+    tick_arrays: [Option<Pubkey>; 3],
+) -> Result<()> {
+    let expected_oracle = Pubkey::create_program_address(
+        &[b"oracle", whirlpool.as_ref(), &[bumps.oracle]],
         whirlpool_program,
-    );
-
-    if let Some(data) = whirlpool_data {
-        // Pass the fee_tier value to new()
-        WhirlpoolPdas::new(data, whirlpool, *whirlpool_program, fee_tier)
-    } else {
-        let (oracle, _) = Pubkey::find_program_address(
-            &[b"oracle", whirlpool.as_ref()],
+    )
+    .map_err(|_| ErrorCode::InvalidWhirlpoolAccount)?;
+    require_keys_eq!(*oracle, expected_oracle, ErrorCode::InvalidWhirlpoolAccount);
+
+    let current_tick_index = parse_tick_index_from_whirlpool(whirlpool_account_data)?;
+    let tick_spacing = parse_tick_spacing_from_whirlpool(whirlpool_account_data)?;
+    let starts = tick_array_start_indexes(current_tick_index, tick_spacing, a_to_b);
+
+    for ((tick_array, start), bump) in tick_arrays.iter().zip(starts).zip(bumps.tick_arrays) {
+        let Some(tick_array) = tick_array else {
+            continue;
+        };
+        let expected = Pubkey::create_program_address(
+            &[b"tick_array", whirlpool.as_ref(), start.to_string().as_bytes(), &[bump]],
             whirlpool_program,
-        );
+        )
+        .map_err(|_| ErrorCode::InvalidTickArrays)?;
+        require_keys_eq!(*tick_array, expected, ErrorCode::InvalidTickArrays);
+    }
 
-        let (fee_tier_pda, _) = Pubkey::find_program_address(
-            &[b"fee_tier", &fee_tier.to_le_bytes()],
-            whirlpool_program,
-        );
-
-        Ok(WhirlpoolPdas {
-            whirlpool,
-            oracle,
-            tick_array_0: Pubkey::default(),
-            tick_array_1: None,
-            tick_array_2: None,
-            fee_tier: fee_tier_pda,
-        })
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_arrays_for_positive_ticks() {
+        assert_eq!(tick_array_start_indexes(100, 1, true), [88, 0, -88]);
+        assert_eq!(tick_array_start_indexes(100, 1, false), [88, 176, 264]);
+        assert_eq!(tick_array_start_indexes(0, 64, true), [0, -5632, -11264]);
+    }
+
+    #[test]
+    fn tick_arrays_for_negative_ticks() {
+        // -1 lies in the array starting at -5632, not the one starting at 0
+        assert_eq!(tick_array_start_indexes(-1, 64, true), [-5632, -11264, -16896]);
+        assert_eq!(tick_array_start_indexes(-1, 64, false), [-5632, 0, 5632]);
+        assert_eq!(tick_array_start_indexes(-5632, 64, false), [-5632, 0, 5632]);
+        assert_eq!(tick_array_start_indexes(-5633, 64, true), [-11264, -16896, -22528]);
+    }
+
+    #[test]
+    fn tick_arrays_at_the_tick_bounds() {
+        assert_eq!(tick_array_start_indexes(-443_636, 8, true), [-444_224, -444_928, -445_632]);
+        assert_eq!(tick_array_start_indexes(443_636, 8, false), [443_520, 444_224, 444_928]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+// Loads the addresses.toml profile for the cluster in Anchor.toml, or for
+// $CLUSTER when set. Shared by scripts and tests.
+const fs = require('fs');
+const path = require('path');
+const toml = require('toml');
+const { PublicKey } = require("@solana/web3.js");
+
+const ROOT = path.join(__dirname, '..');
+
+function clusterProfile(cluster) {
+    const name = cluster.toLowerCase();
+    if (name === "localnet" || name === "localhost" || name.includes("127.0.0.1") || name.includes("localhost")) {
+        return "localnet";
+    }
+    if (name.includes("devnet")) {
+        return "devnet";
+    }
+    if (name.includes("mainnet")) {
+        return "mainnet";
+    }
+    throw new Error(`No address book profile for cluster ${cluster}`);
+}
+
+function toKeys(table) {
+    return Object.fromEntries(
+        Object.entries(table || {}).map(([name, address]) => [name, new PublicKey(address)])
+    );
+}
+
+function loadProfile(cluster) {
+    if (!cluster) {
+        const anchor = toml.parse(fs.readFileSync(path.join(ROOT, 'Anchor.toml'), 'utf8'));
+        cluster = process.env.CLUSTER || anchor.provider.cluster;
+    }
+    const name = clusterProfile(cluster);
+    const book = toml.parse(fs.readFileSync(path.join(ROOT, 'addresses.toml'), 'utf8'));
+    const profile = book[name];
+    if (!profile) {
+        throw new Error(`No ${name} profile in addresses.toml`);
+    }
+    return {
+        cluster: name,
+        url: profile.url,
+        programs: toKeys(profile.programs),
+        mints: toKeys(profile.mints),
+        reserves: toKeys(profile.reserves),
+        pools: toKeys(profile.pools),
+        wallets: toKeys(profile.wallets),
+    };
+}
+
+module.exports = { loadProfile };
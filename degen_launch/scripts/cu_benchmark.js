@@ -0,0 +1,151 @@
+// Reports compute units consumed per top-level instruction, and fails when one of
+// ours goes over its budget.
+//
+// Usage:
+//   node scripts/cu_benchmark.js <signature | file with a base64 transaction>...
+//
+// Signatures are fetched from the cluster; transaction files are simulated with a
+// fresh blockhash and no signature check, so unsigned transactions work too.
+require('dotenv').config();
+const { Connection, VersionedTransaction } = require("@solana/web3.js");
+const fs = require('fs');
+const { loadProfile } = require('./address_book');
+
+const CU_LIMIT = 1_400_000;
+
+// Per-instruction budgets, venue CPIs included. The lender's flash borrow and repay
+// are instructions of their own and are not counted against the strategy.
+const CU_BUDGETS = {
+    ExecuteFlashloanSelfdump: 300_000,
+    ExecuteRoundTripArbitrage: 500_000,
+    ExecuteTriangularArbitrage: 700_000,
+    ExecuteRoute: 900_000,
+};
+
+function connect(profile) {
+    return new Connection(process.env.ANCHOR_PROVIDER_URL || profile.url, "confirmed");
+}
+
+async function fetchLogs(connection, input) {
+    if (fs.existsSync(input)) {
+        const raw = Buffer.from(fs.readFileSync(input, 'utf8').trim(), 'base64');
+        const tx = VersionedTransaction.deserialize(raw);
+        const { value } = await connection.simulateTransaction(tx, {
+            sigVerify: false,
+            replaceRecentBlockhash: true,
+        });
+        if (value.err) {
+            console.warn(`${input}: simulation failed: ${JSON.stringify(value.err)}`);
+        }
+        return value.logs || [];
+    }
+
+    const tx = await connection.getTransaction(input, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+    });
+    if (!tx) {
+        throw new Error(`Transaction ${input} not found`);
+    }
+    return tx.meta.logMessages || [];
+}
+
+// Splits logs into top-level instructions, naming ours by Anchor's "Instruction:" log
+function measure(logs) {
+    const results = [];
+    let depth = 0;
+    let current = null;
+
+    for (const line of logs) {
+        const invoke = line.match(/^Program (\w+) invoke \[(\d+)\]$/);
+        if (invoke) {
+            depth = Number(invoke[2]);
+            if (depth === 1) {
+                current = { program: invoke[1], name: null, units: null };
+                results.push(current);
+            }
+            continue;
+        }
+
+        const name = line.match(/^Program log: Instruction: (\w+)$/);
+        if (name && depth === 1 && current && !current.name) {
+            current.name = name[1];
+            continue;
+        }
+
+        const consumed = line.match(/^Program (\w+) consumed (\d+) of (\d+) compute units$/);
+        if (consumed && depth === 1 && current && consumed[1] === current.program) {
+            current.units = Number(consumed[2]);
+            continue;
+        }
+
+        if (/^Program \w+ (success|failed)/.test(line)) {
+            depth -= 1;
+        }
+    }
+
+    return results;
+}
+
+// Instructions of `programId` that went over their budget, or never reported usage
+function overBudget(results, programId, budgets = CU_BUDGETS) {
+    return results.filter((r) => {
+        const budget = budgets[r.name];
+        return r.program === programId && budget !== undefined && (r.units === null || r.units > budget);
+    });
+}
+
+// Prints the per-instruction table of one transaction and returns what went over budget
+function report(title, results, programId) {
+    const total = results.reduce((sum, r) => sum + (r.units || 0), 0);
+
+    console.log(`\n${title}`);
+    for (const r of results) {
+        const label = r.program === programId
+            ? `degen_launch::${r.name || "unknown"}`
+            : r.program;
+        const units = r.units === null ? "n/a" : r.units.toLocaleString();
+        console.log(`  ${label.padEnd(60)} ${units.padStart(10)} CU`);
+    }
+    const headroom = ((1 - total / CU_LIMIT) * 100).toFixed(1);
+    console.log(`  ${"total".padEnd(60)} ${total.toLocaleString().padStart(10)} CU (${headroom}% of limit left)`);
+
+    const over = overBudget(results, programId);
+    for (const r of over) {
+        console.error(`  ${r.name} is over its ${CU_BUDGETS[r.name].toLocaleString()} CU budget`);
+    }
+    return over;
+}
+
+async function main() {
+    const inputs = process.argv.slice(2);
+    if (inputs.length === 0) {
+        console.error("Usage: node scripts/cu_benchmark.js <signature | tx file>...");
+        process.exit(1);
+    }
+
+    const profile = loadProfile();
+    const programId = profile.programs.degen_launch.toBase58();
+    const connection = connect(profile);
+    let failed = false;
+
+    for (const input of inputs) {
+        const results = measure(await fetchLogs(connection, input));
+        if (report(input, results, programId).length > 0) {
+            failed = true;
+        }
+    }
+
+    if (failed) {
+        process.exit(1);
+    }
+}
+
+if (require.main === module) {
+    main().catch((error) => {
+        console.error("Benchmark failed:", error);
+        process.exit(1);
+    });
+}
+
+module.exports = { CU_BUDGETS, CU_LIMIT, connect, fetchLogs, measure, overBudget, report };
@@ -0,0 +1,344 @@
+// Runs a flash loan selfdump against mainnet state on a local validator and reports
+// the compute units of every instruction in it, failing when one of ours goes over
+// its budget.
+//
+// Usage (after `anchor build`):
+//   node scripts/cu_localnet.js
+//
+// Starts solana-test-validator with the program from target/deploy, the lender and
+// Whirlpool programs, the mainnet USDC reserve and the SOL/USDC pool cloned from
+// $CU_CLONE_URL (the mainnet profile's URL by default), then sets up the config,
+// vaults and stats and sends [compute budget, flash borrow, selfdump, flash repay].
+const anchor = require("@coral-xyz/anchor");
+const {
+    ComputeBudgetProgram,
+    Connection,
+    Keypair,
+    PublicKey,
+    SystemProgram,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    SYSVAR_RENT_PUBKEY,
+    Transaction,
+    TransactionInstruction,
+    sendAndConfirmTransaction,
+} = require("@solana/web3.js");
+const {
+    NATIVE_MINT,
+    TOKEN_PROGRAM_ID,
+    createAssociatedTokenAccountInstruction,
+    createSyncNativeInstruction,
+    getAssociatedTokenAddressSync,
+} = require("@solana/spl-token");
+const { spawn } = require('child_process');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const { loadProfile } = require('./address_book');
+const { CU_LIMIT, fetchLogs, measure, report } = require('./cu_benchmark');
+
+const ROOT = path.join(__dirname, '..');
+const RPC_URL = "http://127.0.0.1:8899";
+const BPF_LOADER_UPGRADEABLE = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// Borrowed in USDC and, as the selfdump does, dumped in lamports of SOL
+const AMOUNT = 100_000_000;
+
+// Solend instruction tags and reserve layout, see programs/degen_launch/src/solend.rs
+const FLASH_BORROW_TAG = 19;
+const FLASH_REPAY_TAG = 20;
+const RESERVE_LENDING_MARKET_OFFSET = 10;
+const RESERVE_LIQUIDITY_SUPPLY_OFFSET = 75;
+const RESERVE_FEE_RECEIVER_OFFSET = 339;
+
+// Whirlpool layout, see programs/degen_launch/src/whirlpool_utils.rs
+const WHIRLPOOLS_CONFIG_OFFSET = 8;
+const TICK_SPACING_OFFSET = 41;
+const TICK_CURRENT_INDEX_OFFSET = 81;
+const TOKEN_MINT_A_OFFSET = 101;
+const TOKEN_VAULT_A_OFFSET = 133;
+const TOKEN_MINT_B_OFFSET = 181;
+const TOKEN_VAULT_B_OFFSET = 213;
+const TICK_ARRAY_SIZE = 88;
+
+const readKey = (data, offset) => new PublicKey(data.subarray(offset, offset + 32));
+const pda = (seeds, programId) => PublicKey.findProgramAddressSync(seeds, programId);
+
+function u64(value) {
+    const buffer = Buffer.alloc(8);
+    buffer.writeBigUInt64LE(BigInt(value));
+    return buffer;
+}
+
+// Reads the reserve's accounts the flash borrow and repay need
+async function resolveLender(connection, lendingProgram, reserve) {
+    const account = await connection.getAccountInfo(reserve);
+    if (!account || !account.owner.equals(lendingProgram)) {
+        throw new Error(`${reserve.toBase58()} is not a ${lendingProgram.toBase58()} reserve`);
+    }
+    const lendingMarket = readKey(account.data, RESERVE_LENDING_MARKET_OFFSET);
+    return {
+        lendingProgram,
+        reserve,
+        lendingMarket,
+        lendingMarketAuthority: pda([lendingMarket.toBuffer()], lendingProgram)[0],
+        liquiditySupply: readKey(account.data, RESERVE_LIQUIDITY_SUPPLY_OFFSET),
+        feeReceiver: readKey(account.data, RESERVE_FEE_RECEIVER_OFFSET),
+    };
+}
+
+// Reads the whirlpool and derives the oracle, fee tier and the tick arrays an A to B
+// swap walks, with their bumps. Tick arrays missing on the cluster come back null.
+async function resolveWhirlpool(connection, whirlpoolProgram, whirlpool) {
+    const { data } = await connection.getAccountInfo(whirlpool);
+    const tickSpacing = data.readUInt16LE(TICK_SPACING_OFFSET);
+    const tickCurrentIndex = data.readInt32LE(TICK_CURRENT_INDEX_OFFSET);
+    const whirlpoolsConfig = readKey(data, WHIRLPOOLS_CONFIG_OFFSET);
+
+    const ticksInArray = TICK_ARRAY_SIZE * tickSpacing;
+    const start = Math.floor(tickCurrentIndex / ticksInArray) * ticksInArray;
+    const tickArrays = [0, 1, 2].map((i) =>
+        pda([Buffer.from("tick_array"), whirlpool.toBuffer(), Buffer.from(String(start - i * ticksInArray))], whirlpoolProgram)
+    );
+    const existing = await connection.getMultipleAccountsInfo(tickArrays.map(([address]) => address));
+
+    const spacing = Buffer.alloc(2);
+    spacing.writeUInt16LE(tickSpacing);
+    const [oracle, oracleBump] = pda([Buffer.from("oracle"), whirlpool.toBuffer()], whirlpoolProgram);
+
+    return {
+        program: whirlpoolProgram,
+        whirlpool,
+        tokenMintA: readKey(data, TOKEN_MINT_A_OFFSET),
+        tokenVaultA: readKey(data, TOKEN_VAULT_A_OFFSET),
+        tokenMintB: readKey(data, TOKEN_MINT_B_OFFSET),
+        tokenVaultB: readKey(data, TOKEN_VAULT_B_OFFSET),
+        feeTier: pda([Buffer.from("fee_tier"), whirlpoolsConfig.toBuffer(), spacing], whirlpoolProgram)[0],
+        oracle,
+        tickArrays: tickArrays.map(([address], i) => (existing[i] ? address : null)),
+        bumps: { oracle: oracleBump, tickArrays: tickArrays.map(([, bump]) => bump) },
+    };
+}
+
+function flashBorrowIx(lender, destination, amount) {
+    return new TransactionInstruction({
+        programId: lender.lendingProgram,
+        keys: [
+            { pubkey: lender.liquiditySupply, isSigner: false, isWritable: true },
+            { pubkey: destination, isSigner: false, isWritable: true },
+            { pubkey: lender.reserve, isSigner: false, isWritable: true },
+            { pubkey: lender.lendingMarket, isSigner: false, isWritable: false },
+            { pubkey: lender.lendingMarketAuthority, isSigner: false, isWritable: false },
+            { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.concat([Buffer.from([FLASH_BORROW_TAG]), u64(amount)]),
+    });
+}
+
+// The reserve's own fee receiver doubles as the host, as in the client
+function flashRepayIx(lender, source, amount, borrowIndex, authority) {
+    return new TransactionInstruction({
+        programId: lender.lendingProgram,
+        keys: [
+            { pubkey: source, isSigner: false, isWritable: true },
+            { pubkey: lender.liquiditySupply, isSigner: false, isWritable: true },
+            { pubkey: lender.feeReceiver, isSigner: false, isWritable: true },
+            { pubkey: lender.feeReceiver, isSigner: false, isWritable: true },
+            { pubkey: lender.reserve, isSigner: false, isWritable: true },
+            { pubkey: lender.lendingMarket, isSigner: false, isWritable: false },
+            { pubkey: authority, isSigner: true, isWritable: false },
+            { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.concat([Buffer.from([FLASH_REPAY_TAG]), u64(amount), Buffer.from([borrowIndex])]),
+    });
+}
+
+async function startValidator(args) {
+    const ledger = fs.mkdtempSync(path.join(os.tmpdir(), "cu-localnet-"));
+    const validator = spawn("solana-test-validator", ["--reset", "--quiet", "--ledger", ledger, ...args], {
+        stdio: "ignore",
+    });
+    validator.on("exit", (code) => {
+        if (code) {
+            console.error(`solana-test-validator exited with ${code}, see ${ledger}/validator.log`);
+        }
+    });
+
+    const connection = new Connection(RPC_URL, "confirmed");
+    // Cloning from the remote cluster happens before the RPC comes up
+    for (let attempt = 0; attempt < 120; attempt++) {
+        if (validator.exitCode !== null) {
+            break;
+        }
+        try {
+            await connection.getLatestBlockhash();
+            return { validator, connection };
+        } catch (_) {
+            await new Promise((resolve) => setTimeout(resolve, 1000));
+        }
+    }
+    validator.kill();
+    throw new Error("solana-test-validator did not start");
+}
+
+async function main() {
+    const mainnet = loadProfile("mainnet");
+    const remote = new Connection(process.env.CU_CLONE_URL || mainnet.url, "confirmed");
+    const programId = mainnet.programs.degen_launch;
+    const usdc = mainnet.mints.usdc;
+    const pool = await resolveWhirlpool(remote, mainnet.programs.whirlpool, mainnet.pools.sol_usdc_64);
+    const lender = await resolveLender(remote, mainnet.programs.lending, mainnet.reserves.usdc);
+    if (!pool.tokenMintA.equals(NATIVE_MINT) || !pool.tokenMintB.equals(usdc) || !pool.tickArrays[0]) {
+        throw new Error(`${pool.whirlpool.toBase58()} is not a SOL/USDC pool with an initialized current tick array`);
+    }
+
+    const so = path.join(ROOT, "target/deploy/degen_launch.so");
+    const idlPath = path.join(ROOT, "target/idl/degen_launch.json");
+    if (!fs.existsSync(so) || !fs.existsSync(idlPath)) {
+        throw new Error("Run `anchor build` first");
+    }
+
+    const payer = Keypair.generate();
+    const clones = [
+        pool.whirlpool, pool.tokenVaultA, pool.tokenVaultB, pool.tokenMintA, pool.tokenMintB, pool.feeTier,
+        ...pool.tickArrays.filter(Boolean),
+        lender.reserve, lender.lendingMarket, lender.liquiditySupply, lender.feeReceiver,
+    ];
+    const { validator, connection } = await startValidator([
+        "--url", remote.rpcEndpoint,
+        "--warp-slot", String(await remote.getSlot()),
+        "--mint", payer.publicKey.toBase58(),
+        "--clone-upgradeable-program", pool.program.toBase58(),
+        "--clone-upgradeable-program", lender.lendingProgram.toBase58(),
+        ...clones.flatMap((address) => ["--clone", address.toBase58()]),
+        "--upgradeable-program", programId.toBase58(), so, payer.publicKey.toBase58(),
+    ]);
+
+    try {
+        const provider = new anchor.AnchorProvider(connection, new anchor.Wallet(payer), { commitment: "confirmed" });
+        const idl = JSON.parse(fs.readFileSync(idlPath, 'utf8'));
+        const program = new anchor.Program(idl, programId, provider);
+
+        const [config] = pda([Buffer.from("config")], programId);
+        const [vaultAuthority] = pda([Buffer.from("vault_authority")], programId);
+        const vault = (mint) => pda([Buffer.from("vault"), mint.toBuffer()], programId)[0];
+        const ledger = (mint) => pda([Buffer.from("vault_ledger"), mint.toBuffer()], programId)[0];
+        const [stats] = pda([Buffer.from("strategy_stats"), Buffer.from("selfdump"), pool.whirlpool.toBuffer()], programId);
+        const operatorUsdc = getAssociatedTokenAddressSync(usdc, payer.publicKey);
+
+        await program.methods
+            .initializeConfig({
+                lendingProgram: lender.lendingProgram,
+                allowedReserves: [lender.reserve],
+                allowedPools: [pool.whirlpool],
+                maxBorrowAmount: new anchor.BN(AMOUNT),
+                minProfit: new anchor.BN(0),
+                treasury: payer.publicKey,
+                operatorRewardBps: 1000,
+                beneficiaries: [],
+            })
+            .accounts({
+                config,
+                admin: payer.publicKey,
+                program: programId,
+                programData: pda([programId.toBuffer()], BPF_LOADER_UPGRADEABLE)[0],
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+        await program.methods
+            .addRole({ operator: {} }, payer.publicKey)
+            .accounts({ config, admin: payer.publicKey })
+            .rpc();
+        for (const mint of [pool.tokenMintA, pool.tokenMintB]) {
+            await program.methods
+                .initializeVault(new anchor.BN(0))
+                .accounts({
+                    config,
+                    admin: payer.publicKey,
+                    vaultAuthority,
+                    mint,
+                    vault: vault(mint),
+                    ledger: ledger(mint),
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .rpc();
+        }
+        await program.methods
+            .initializeStrategyStats({ selfdump: {} }, pool.whirlpool)
+            .accounts({ config, admin: payer.publicKey, stats, systemProgram: SystemProgram.programId })
+            .rpc();
+
+        // The SOL the selfdump sells, and the account the operator's share of the profit lands in
+        await sendAndConfirmTransaction(
+            connection,
+            new Transaction().add(
+                SystemProgram.transfer({ fromPubkey: payer.publicKey, toPubkey: vault(NATIVE_MINT), lamports: AMOUNT }),
+                createSyncNativeInstruction(vault(NATIVE_MINT)),
+                createAssociatedTokenAccountInstruction(payer.publicKey, operatorUsdc, payer.publicKey, usdc)
+            ),
+            [payer]
+        );
+
+        const selfdump = await program.methods
+            .executeFlashloanSelfdump(new anchor.BN(AMOUNT), new anchor.BN(0), pool.bumps, new anchor.BN(0))
+            .accounts({
+                config,
+                operator: payer.publicKey,
+                reserve: lender.reserve,
+                reserveLiquiditySupply: lender.liquiditySupply,
+                instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                userLiquidity: vault(usdc),
+                whirlpoolProgram: pool.program,
+                vaultAuthority,
+                tokenOwnerAccount: vault(NATIVE_MINT),
+                tokenVaultA: pool.tokenVaultA,
+                tokenVaultB: pool.tokenVaultB,
+                tokenMintA: pool.tokenMintA,
+                tokenMintB: pool.tokenMintB,
+                whirlpool: pool.whirlpool,
+                tickArray0: pool.tickArrays[0],
+                oracle: pool.oracle,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                feeTier: pool.feeTier,
+                tickArray1: pool.tickArrays[1],
+                tickArray2: pool.tickArrays[2],
+                stats,
+                receipt: null,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts([{ pubkey: operatorUsdc, isSigner: false, isWritable: true }])
+            .instruction();
+
+        // The borrow sits at index 1, behind the compute budget
+        const tx = new Transaction().add(
+            ComputeBudgetProgram.setComputeUnitLimit({ units: CU_LIMIT }),
+            flashBorrowIx(lender, vault(usdc), AMOUNT),
+            selfdump,
+            flashRepayIx(lender, vault(usdc), AMOUNT, 1, payer.publicKey)
+        );
+        // Sent unsimulated so a failing run still lands and its logs can be reported
+        const blockhash = await connection.getLatestBlockhash();
+        tx.recentBlockhash = blockhash.blockhash;
+        const signature = await connection.sendTransaction(tx, [payer], { skipPreflight: true });
+        const { value } = await connection.confirmTransaction({ signature, ...blockhash }, "confirmed");
+
+        const logs = await fetchLogs(connection, signature);
+        const over = report(`localnet selfdump ${signature}`, measure(logs), programId.toBase58());
+        if (value.err) {
+            throw new Error(`Selfdump failed: ${JSON.stringify(value.err)}\n${logs.join("\n")}`);
+        }
+        if (over.length > 0) {
+            process.exitCode = 1;
+        }
+    } finally {
+        validator.kill();
+    }
+}
+
+main().catch((error) => {
+    console.error("Localnet benchmark failed:", error);
+    process.exit(1);
+});
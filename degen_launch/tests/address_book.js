@@ -0,0 +1,32 @@
+// The address book every script and test resolves names through
+const { expect } = require("chai");
+const { loadProfile } = require('../scripts/address_book');
+
+const PROGRAM = "6UBFGLf5YBdVAzdzzoMhQsL3pM1KjgRp7EgVDCP4UqGV";
+const WHIRLPOOL = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+describe("address book", () => {
+    for (const cluster of ["localnet", "devnet", "mainnet"]) {
+        it(`loads the ${cluster} profile`, () => {
+            const profile = loadProfile(cluster);
+            expect(profile.cluster).to.equal(cluster);
+            expect(profile.programs.degen_launch.toBase58()).to.equal(PROGRAM);
+            expect(profile.programs.whirlpool.toBase58()).to.equal(WHIRLPOOL);
+            expect(profile.programs.lending).to.exist;
+        });
+    }
+
+    it("maps RPC URLs onto profiles", () => {
+        expect(loadProfile("http://127.0.0.1:8899").cluster).to.equal("localnet");
+        expect(loadProfile("https://api.mainnet-beta.solana.com").cluster).to.equal("mainnet");
+        expect(() => loadProfile("testnet")).to.throw(/No address book profile/);
+    });
+
+    it("has the mainnet reserves and pools", () => {
+        const mainnet = loadProfile("mainnet");
+        expect(mainnet.reserves.usdc.toBase58()).to.equal("BgxfHJDzm44T7XG68MYKx7YisTjZu73tVovyZSjJMpmw");
+        expect(mainnet.pools.sol_usdc_64.toBase58()).to.equal("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ");
+        // The reserves are Solend main market reserves, so Solend has to be the lender
+        expect(mainnet.programs.lending.toBase58()).to.equal("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+    });
+});
@@ -0,0 +1,62 @@
+// Compute unit budgets. The log parsing runs on every `anchor test`; the budget
+// check runs against real transactions listed in $CU_BENCH_TXS (comma separated
+// signatures or base64 transaction files), e.g. from a devnet strategy run.
+// `yarn bench:cu:localnet` runs a selfdump against cloned mainnet state instead.
+const { expect } = require("chai");
+const { loadProfile } = require('../scripts/address_book');
+const { CU_BUDGETS, connect, fetchLogs, measure, overBudget } = require('../scripts/cu_benchmark');
+
+const PROGRAM = "6UBFGLf5YBdVAzdzzoMhQsL3pM1KjgRp7EgVDCP4UqGV";
+const LENDER = "So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo";
+const WHIRLPOOL = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+describe("cu benchmark", () => {
+    const logs = [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+        "Program ComputeBudget111111111111111111111111111111 success",
+        `Program ${LENDER} invoke [1]`,
+        "Program log: Instruction: Flash Borrow Reserve Liquidity",
+        `Program ${LENDER} consumed 28000 of 1399850 compute units`,
+        `Program ${LENDER} success`,
+        `Program ${PROGRAM} invoke [1]`,
+        "Program log: Instruction: ExecuteFlashloanSelfdump",
+        `Program ${WHIRLPOOL} invoke [2]`,
+        "Program log: Instruction: Swap",
+        `Program ${WHIRLPOOL} consumed 61000 of 1330000 compute units`,
+        `Program ${WHIRLPOOL} success`,
+        `Program ${PROGRAM} consumed 126000 of 1371850 compute units`,
+        `Program ${PROGRAM} success`,
+        `Program ${LENDER} invoke [1]`,
+        "Program log: Instruction: Flash Repay Reserve Liquidity",
+        `Program ${LENDER} consumed 38000 of 1245850 compute units`,
+        `Program ${LENDER} success`,
+    ];
+
+    it("attributes nested usage to the top-level instruction", () => {
+        const results = measure(logs);
+        expect(results).to.have.length(4);
+        expect(results[2]).to.deep.equal({ program: PROGRAM, name: "ExecuteFlashloanSelfdump", units: 126000 });
+    });
+
+    it("flags instructions over their budget", () => {
+        const results = measure(logs);
+        expect(overBudget(results, PROGRAM)).to.be.empty;
+        expect(overBudget(results, PROGRAM, { ExecuteFlashloanSelfdump: 100000 })).to.have.length(1);
+    });
+
+    it("keeps strategy transactions within budget", async function () {
+        const inputs = (process.env.CU_BENCH_TXS || "").split(",").filter(Boolean);
+        if (inputs.length === 0) {
+            this.skip();
+        }
+
+        const profile = loadProfile();
+        const connection = connect(profile);
+        const programId = profile.programs.degen_launch.toBase58();
+        for (const input of inputs) {
+            const results = measure(await fetchLogs(connection, input));
+            const over = overBudget(results, programId).map((r) => `${r.name}: ${r.units} > ${CU_BUDGETS[r.name]}`);
+            expect(over, input).to.be.empty;
+        }
+    });
+});